@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::*;
+
+/// A pair of colliders that might touch, with the later collider first.
+pub type ColliderPair = (ColliderHandle, ColliderHandle);
+
+/// Generates candidate collider pairs for the narrow phase.
+///
+/// Implementations must be conservative, any pair whose shapes (including
+/// their margins) touch has to be reported. Extra pairs only cost time.
+pub trait BroadPhase {
+    /// Candidate pairs for the current collider positions. Each pair is
+    /// ordered with the later collider (by arena index) first.
+    fn find_pairs(&mut self, col_set: &ColliderSet) -> Vec<ColliderPair>;
+
+    /// Called when a collider is removed from the world.
+    fn remove(&mut self, _handle: ColliderHandle) {}
+
+    /// Called when the world is reset.
+    fn clear(&mut self) {}
+}
+
+/// Orders a pair like the brute force broad phase does.
+pub fn ordered_pair(a: ColliderHandle, b: ColliderHandle) -> ColliderPair {
+    if a.0 > b.0 {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Tests every pair, O(n²).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BruteForceBroadPhase;
+
+impl BroadPhase for BruteForceBroadPhase {
+    fn find_pairs(&mut self, col_set: &ColliderSet) -> Vec<ColliderPair> {
+        let keys = col_set.iter().map(|(idx, _)| idx).collect_vec();
+
+        let mut pairs = vec![];
+
+        for (i, idx_a) in keys.iter().enumerate() {
+            for idx_b in keys.iter().take(i) {
+                pairs.push(ordered_pair(*idx_a, *idx_b));
+            }
+        }
+
+        pairs
+    }
+}
+
+/// Uniform grid on top of `SpatialHash`, best for many similarly sized
+/// colliders.
+pub struct SpatialHashBroadPhase {
+    pub hash: SpatialHash,
+}
+
+impl SpatialHashBroadPhase {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            hash: SpatialHash::new(cell_size),
+        }
+    }
+}
+
+impl Default for SpatialHashBroadPhase {
+    fn default() -> Self {
+        Self::new(2.0)
+    }
+}
+
+impl BroadPhase for SpatialHashBroadPhase {
+    fn find_pairs(&mut self, col_set: &ColliderSet) -> Vec<ColliderPair> {
+        // Colliders bigger than a cell would widen every query, they are
+        // kept out of the hash and tested against everything instead.
+        let cell_size = self.hash.cell_size;
+        let mut oversized = HashSet::new();
+        let mut aabbs = Vec::with_capacity(col_set.len());
+
+        self.hash.max_radius = 0.0;
+
+        for (handle, collider) in col_set.iter() {
+            let aabb = collider.calculate_aabb();
+            let radius = aabb.size().length() * 0.5 + collider.margin;
+            let id = handle.0.to_bits();
+
+            if radius > cell_size {
+                self.hash.remove(id);
+                oversized.insert(handle);
+            } else {
+                self.hash.set_point(id, aabb.center(), radius);
+            }
+
+            aabbs.push((handle, aabb.grown(collider.margin)));
+        }
+
+        let mut pairs = vec![];
+
+        for (idx_a, _) in col_set.iter() {
+            let Some(point) = self.hash.points.get(&idx_a.0.to_bits()).copied() else { continue; };
+
+            for other in self.hash.query(point.position, point.radius) {
+                let idx_b = ColliderHandle(Index::from_bits(other.id).unwrap());
+
+                if idx_a.0 > idx_b.0 {
+                    pairs.push((idx_a, idx_b));
+                }
+            }
+        }
+
+        for &(idx_a, aabb_a) in aabbs.iter() {
+            if !oversized.contains(&idx_a) {
+                continue;
+            }
+
+            for &(idx_b, aabb_b) in aabbs.iter() {
+                if idx_a == idx_b || (oversized.contains(&idx_b) && idx_a.0 < idx_b.0) {
+                    continue;
+                }
+
+                if aabb_a.intersects(&aabb_b) {
+                    pairs.push(ordered_pair(idx_a, idx_b));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    fn remove(&mut self, handle: ColliderHandle) {
+        self.hash.remove(handle.0.to_bits());
+    }
+
+    fn clear(&mut self) {
+        self.hash = SpatialHash::new(self.hash.cell_size);
+    }
+}
@@ -0,0 +1,196 @@
+use bvh_arena::{BoundingVolume, Bvh, VolumeHandle};
+
+use crate::*;
+
+impl BoundingVolume for AABB {
+    fn merge(self, other: Self) -> Self {
+        AABB::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Perimeter instead of the area, so that flat boxes around segments
+    /// still count as big.
+    fn area(&self) -> f32 {
+        let size = self.size();
+        2.0 * (size.x + size.y)
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.intersects(other)
+    }
+}
+
+struct BvhProxy {
+    volume: VolumeHandle,
+    fat_aabb: AABB,
+    seen: bool,
+}
+
+/// Dynamic AABB tree over collider bounds.
+///
+/// Each collider is stored with a fattened AABB and only reinserted once
+/// it moves out of it, which keeps slow or resting colliders cheap. Works
+/// well with very mixed collider sizes where a uniform grid struggles.
+pub struct BvhBroadPhase {
+    tree: Bvh<ColliderHandle, AABB>,
+    proxies: HashMap<ColliderHandle, BvhProxy>,
+    /// How much the stored AABBs are grown on every side.
+    pub fat_margin: f32,
+}
+
+impl BvhBroadPhase {
+    pub fn new(fat_margin: f32) -> Self {
+        Self {
+            tree: Bvh::default(),
+            proxies: HashMap::new(),
+            fat_margin,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.proxies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proxies.is_empty()
+    }
+
+    pub fn remove(&mut self, handle: ColliderHandle) {
+        if let Some(proxy) = self.proxies.remove(&handle) {
+            self.tree.remove(proxy.volume);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.proxies.clear();
+    }
+
+    /// Refits the tree to the current collider AABBs, dropping colliders that
+    /// no longer exist.
+    pub fn update(&mut self, col_set: &ColliderSet) {
+        for proxy in self.proxies.values_mut() {
+            proxy.seen = false;
+        }
+
+        for (handle, collider) in col_set.iter() {
+            self.update_collider(handle, collider);
+        }
+
+        let tree = &mut self.tree;
+
+        self.proxies.retain(|_, proxy| {
+            if !proxy.seen {
+                tree.remove(proxy.volume);
+            }
+
+            proxy.seen
+        });
+    }
+
+    /// Inserts a single collider or refits it if it left its fat AABB.
+    pub fn update_collider(&mut self, handle: ColliderHandle, collider: &Collider) {
+        let aabb = collider.calculate_aabb().grown(collider.margin);
+
+        if let Some(proxy) = self.proxies.get_mut(&handle) {
+            proxy.seen = true;
+
+            if proxy.fat_aabb.contains_aabb(&aabb) {
+                return;
+            }
+
+            self.tree.remove(proxy.volume);
+        }
+
+        let fat_aabb = aabb.grown(self.fat_margin);
+        let volume = self.tree.insert(handle, fat_aabb);
+
+        self.proxies.insert(
+            handle,
+            BvhProxy {
+                volume,
+                fat_aabb,
+                seen: true,
+            },
+        );
+    }
+
+    /// Candidate pairs whose fattened AABBs overlap, with the later collider first.
+    pub fn pairs(&self) -> Vec<ColliderPair> {
+        let mut pairs = vec![];
+
+        self.tree.for_each_overlaping_pair(|a, b| {
+            pairs.push(ordered_pair(*a, *b));
+        });
+
+        pairs
+    }
+
+    /// Colliders whose fattened AABBs overlap `aabb`.
+    pub fn overlapping(&self, aabb: &AABB) -> Vec<ColliderHandle> {
+        let mut handles = vec![];
+
+        self.tree.for_each_overlaps(aabb, |handle| {
+            handles.push(*handle);
+        });
+
+        handles
+    }
+}
+
+impl Default for BvhBroadPhase {
+    fn default() -> Self {
+        Self::new(0.1)
+    }
+}
+
+impl BroadPhase for BvhBroadPhase {
+    fn find_pairs(&mut self, col_set: &ColliderSet) -> Vec<ColliderPair> {
+        self.update(col_set);
+        self.pairs()
+    }
+
+    fn remove(&mut self, handle: ColliderHandle) {
+        BvhBroadPhase::remove(self, handle);
+    }
+
+    fn clear(&mut self) {
+        BvhBroadPhase::clear(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bvh_tracks_moving_colliders() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let mut colliders = vec![];
+
+        for x in [0.0, 3.0] {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(x, 0.0)).build());
+            colliders.push(
+                physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body),
+            );
+        }
+
+        let mut bvh = BvhBroadPhase::default();
+
+        bvh.update(&physics.col_set);
+        assert_eq!(bvh.len(), 2);
+        assert!(bvh.pairs().is_empty());
+
+        physics.col_set.get_mut(colliders[1]).unwrap().absolute_transform =
+            Affine2::from_translation(vec2(0.9, 0.0));
+
+        bvh.update(&physics.col_set);
+        assert_eq!(bvh.pairs(), vec![(colliders[1], colliders[0])]);
+
+        physics.col_set.remove_ignoring_parent(colliders[0]);
+
+        bvh.update(&physics.col_set);
+        assert_eq!(bvh.len(), 1);
+        assert!(bvh.pairs().is_empty());
+    }
+}
@@ -8,11 +8,15 @@ pub struct Collider {
     pub user_data: u128,
     pub parent: Option<RigidBodyHandle>,
 
-    pub radius: f32,
+    /// Contact skin added around the shape, zero by default.
+    pub margin: f32,
 
+    pub density: f32,
     pub mass_override: Option<f32>,
 
     pub flags: ColliderFlags,
+    /// Events this collider reports, none by default.
+    pub active_events: ActiveEvents,
 
     pub collision_groups: InteractionGroups,
 
@@ -37,16 +41,45 @@ impl Collider {
         //     .angle_between(up)
     }
 
+    /// Mass properties relative to the parent body, `mass_override`
+    /// rescales the shape's mass while keeping its distribution.
+    pub fn mass_properties(&self) -> MassProperties {
+        self.scaled_mass_properties(Vec2::ONE)
+    }
+
+    /// Same as `mass_properties` inside a body scaled by `scale`. Scaling
+    /// keeps the density, `mass_override` stays the absolute mass.
+    pub fn scaled_mass_properties(&self, scale: Vec2) -> MassProperties {
+        let props = self
+            .shape
+            .mass_properties(self.density)
+            .transformed(self.offset)
+            .scaled(scale);
+
+        match self.mass_override {
+            Some(mass) => props.with_mass(mass),
+            None => props,
+        }
+    }
+
     pub fn mass(&self) -> f32 {
-        self.mass_override.unwrap_or(self.radius * 2.0)
+        self.mass_properties().mass
     }
 
+    /// Moment of inertia around the collider's own center of mass.
     pub fn inertia(&self) -> f32 {
-        let mass = self.mass();
-        let d = self.offset.translation.length();
-        let inertia = 0.5 * mass * self.radius.powi(2);
+        self.mass_properties().inertia
+    }
+
+    /// Radius of the world space circle around the collider's origin
+    /// enclosing its shape and margin.
+    pub fn bounding_radius(&self) -> f32 {
+        let aabb = self.calculate_aabb();
+        let origin = self.absolute_translation();
 
-        inertia + mass * d.powi(2)
+        let extent = (aabb.min - origin).abs().max((aabb.max - origin).abs());
+
+        extent.length() + self.margin
     }
 
     pub fn is_sensor(&self) -> bool {
@@ -84,8 +117,28 @@ impl Default for ColliderFlags {
     }
 }
 
+bitflags::bitflags! {
+    /// Events a collider opts into, a pair reports an event if either of
+    /// its colliders enables it.
+    pub struct ActiveEvents: u32 {
+        /// `CollisionEvent`s for touching solid colliders.
+        const COLLISION_EVENTS = 1 << 0;
+        /// `SensorEvent`s for sensor overlaps.
+        const SENSOR_EVENTS = 1 << 1;
+        /// Reserved for contact force events, nothing produces them yet.
+        const CONTACT_FORCE_EVENTS = 1 << 2;
+    }
+}
+
+impl Default for ActiveEvents {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 pub struct ColliderSet {
-    time_data: Rc<TimeData>,
+    time_data: Rc<Cell<TimeData>>,
+    event_history: Rc<RefCell<EventHistory>>,
 
     pub arena: Arena<Collider>,
 
@@ -93,9 +146,10 @@ pub struct ColliderSet {
 }
 
 impl ColliderSet {
-    pub fn new(time_data: Rc<TimeData>) -> Self {
+    pub fn new(time_data: Rc<Cell<TimeData>>, event_history: Rc<RefCell<EventHistory>>) -> Self {
         Self {
             time_data,
+            event_history,
             arena: Arena::new(),
             group_arenas: HashMap::new(),
         }
@@ -143,8 +197,8 @@ impl ColliderSet {
                     if body.colliders.len() == 0 {
                         remove_rbd = true;
 
-                        push_event(PhysicsEvent {
-                            time_data: *self.time_data,
+                        self.event_history.borrow_mut().push(PhysicsEvent {
+                            time_data: self.time_data.get(),
                             position: Some(body.position),
                             message: "rbd removed because colliders.len() == 0".into(),
                             severity: Severity::Info,
@@ -171,6 +225,10 @@ impl ColliderSet {
     ) -> ColliderHandle {
         collider.parent = Some(rbd_handle);
 
+        if let Some(rbd) = rbd_set.get(rbd_handle) {
+            collider.absolute_transform = rbd.transform() * collider.offset;
+        }
+
         let col_group = collider.collision_groups.memberships;
 
         let col_handle = self.arena.insert(collider);
@@ -201,9 +259,11 @@ pub struct ColliderBuilder {
     absolute_transform: Affine2,
     user_data: u128,
     parent: Option<RigidBodyHandle>,
-    radius: f32,
+    margin: f32,
+    density: f32,
     mass_override: Option<f32>,
     flags: ColliderFlags,
+    active_events: ActiveEvents,
     collision_groups: InteractionGroups,
     shape: Box<dyn Shape>,
 }
@@ -215,14 +275,28 @@ impl ColliderBuilder {
             absolute_transform: Affine2::IDENTITY,
             user_data: 0,
             parent: None,
-            radius: 0.5,
+            margin: 0.0,
+            density: 1.0,
             mass_override: None,
             flags: ColliderFlags::default(),
+            active_events: ActiveEvents::default(),
             collision_groups: InteractionGroups::default(),
             shape: Box::new(Ball::new(0.5)),
         }
     }
 
+    pub fn ball(radius: f32) -> Self {
+        Self::new().shape(Box::new(Ball::new(radius)))
+    }
+
+    pub fn cuboid(half_width: f32, half_height: f32) -> Self {
+        Self::new().shape(Box::new(Cuboid::new(vec2(half_width, half_height))))
+    }
+
+    pub fn capsule(half_height: f32, radius: f32) -> Self {
+        Self::new().shape(Box::new(Capsule::new(half_height, radius)))
+    }
+
     pub fn offset(mut self, offset: Affine2) -> Self {
         self.offset = offset;
         self
@@ -238,6 +312,11 @@ impl ColliderBuilder {
         self
     }
 
+    pub fn density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
     pub fn user_data(mut self, user_data: u128) -> Self {
         self.user_data = user_data;
         self
@@ -248,8 +327,8 @@ impl ColliderBuilder {
         self
     }
 
-    pub fn radius(mut self, radius: f32) -> Self {
-        self.radius = radius;
+    pub fn margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
         self
     }
 
@@ -258,6 +337,11 @@ impl ColliderBuilder {
         self
     }
 
+    pub fn active_events(mut self, active_events: ActiveEvents) -> Self {
+        self.active_events = active_events;
+        self
+    }
+
     pub fn collision_groups(mut self, collision_groups: InteractionGroups) -> Self {
         self.collision_groups = collision_groups;
         self
@@ -274,9 +358,11 @@ impl ColliderBuilder {
             absolute_transform: self.absolute_transform,
             user_data: self.user_data,
             parent: self.parent,
+            density: self.density,
             mass_override: self.mass_override,
-            radius: self.radius,
+            margin: self.margin,
             flags: self.flags,
+            active_events: self.active_events,
             collision_groups: self.collision_groups,
             shape: self.shape,
         }
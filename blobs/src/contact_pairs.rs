@@ -0,0 +1,107 @@
+use crate::*;
+
+struct ContactPairState {
+    event: CollisionEvent,
+    touched: bool,
+}
+
+/// Persistent set of touching collider pairs, tracks contact lifetimes
+/// across substeps and steps.
+#[derive(Default)]
+pub struct ContactPairSet {
+    pairs: HashMap<ColliderPair, ContactPairState>,
+}
+
+impl ContactPairSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Records a touching pair for the current step, returns `true` if the
+    /// pair wasn't touching before.
+    ///
+    /// The stored data is `Started` during the step the pair started
+    /// touching and `Ongoing` afterwards.
+    pub fn touch(&mut self, event: CollisionEvent) -> bool {
+        let key = ordered_pair(event.col_handle_a, event.col_handle_b);
+
+        let kind = match self.pairs.get(&key) {
+            None => CollisionEventKind::Started,
+            // Touched again in a later substep of the same step.
+            Some(state) if state.touched => state.event.kind,
+            Some(_) => CollisionEventKind::Ongoing,
+        };
+
+        let event = CollisionEvent { kind, ..event };
+
+        self.pairs
+            .insert(
+                key,
+                ContactPairState {
+                    event,
+                    touched: true,
+                },
+            )
+            .is_none()
+    }
+
+    /// Removes every pair that didn't touch during the step, returning their
+    /// last known data as `Stopped` events.
+    pub fn finish_step(&mut self) -> Vec<CollisionEvent> {
+        let mut stopped = vec![];
+
+        self.pairs.retain(|_, state| {
+            if !state.touched {
+                stopped.push(CollisionEvent {
+                    kind: CollisionEventKind::Stopped,
+                    ..state.event
+                });
+            }
+
+            std::mem::take(&mut state.touched)
+        });
+
+        stopped
+    }
+
+    /// Removes every pair involving `handle`, returning their last known
+    /// data as `Stopped` events.
+    pub fn remove_collider(&mut self, handle: ColliderHandle) -> Vec<CollisionEvent> {
+        let mut stopped = vec![];
+
+        self.pairs.retain(|(a, b), state| {
+            let involved = *a == handle || *b == handle;
+
+            if involved {
+                stopped.push(CollisionEvent {
+                    kind: CollisionEventKind::Stopped,
+                    ..state.event
+                });
+            }
+
+            !involved
+        });
+
+        stopped
+    }
+
+    pub fn get(&self, a: ColliderHandle, b: ColliderHandle) -> Option<&CollisionEvent> {
+        self.pairs.get(&ordered_pair(a, b)).map(|state| &state.event)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CollisionEvent> {
+        self.pairs.values().map(|state| &state.event)
+    }
+
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+    }
+}
@@ -19,10 +19,23 @@ pub struct DebugJoint {
     pub body_b: Vec2,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct DebugCollider {
     pub transform: Affine2,
     pub radius: f32,
+    pub shape: DebugShape,
+}
+
+#[derive(Clone, Debug)]
+pub enum DebugShape {
+    Ball { radius: f32 },
+    Cuboid { half_extents: Vec2 },
+    /// Points are in the collider's local space.
+    ConvexPolygon { points: Vec<Vec2> },
+    Capsule { half_height: f32, radius: f32 },
+    /// Points are in the collider's local space.
+    Polyline { points: Vec<Vec2>, one_sided: bool },
+    Heightfield { heights: Vec<f32>, scale: Vec2 },
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -31,13 +44,80 @@ pub struct DebugSpring {
     pub body_b: Vec2,
 }
 
+/// Downcasts instead of trusting `shape_type`, which custom shapes may
+/// override with a built-in variant.
+fn debug_shape(shape: &dyn Shape) -> DebugShape {
+    if let Some(ball) = shape.as_ball() {
+        return DebugShape::Ball {
+            radius: ball.radius,
+        };
+    }
+
+    if let Some(cuboid) = shape.as_cuboid() {
+        return DebugShape::Cuboid {
+            half_extents: cuboid.half_extents,
+        };
+    }
+
+    if let Some(polygon) = shape.as_convex_polygon() {
+        return DebugShape::ConvexPolygon {
+            points: polygon.points.clone(),
+        };
+    }
+
+    if let Some(capsule) = shape.as_capsule() {
+        return DebugShape::Capsule {
+            half_height: capsule.half_height,
+            radius: capsule.radius,
+        };
+    }
+
+    if let Some(polyline) = shape.as_polyline() {
+        return DebugShape::Polyline {
+            points: polyline.points.clone(),
+            one_sided: polyline.one_sided,
+        };
+    }
+
+    if let Some(heightfield) = shape.as_heightfield() {
+        return DebugShape::Heightfield {
+            heights: heightfield.heights.clone(),
+            scale: heightfield.scale,
+        };
+    }
+
+    // Outline a custom convex shape by sampling its support points, fall
+    // back to its bounding box otherwise.
+    let mut points: Vec<Vec2> = (0..32)
+        .filter_map(|i| {
+            let direction = Vec2::from_angle(i as f32 / 32.0 * std::f32::consts::TAU);
+            shape.support_point(Affine2::IDENTITY, direction)
+        })
+        .collect();
+
+    points.dedup_by(|a, b| a.distance_squared(*b) < 1e-8);
+
+    if points.len() < 3 {
+        let aabb = shape.calculate_aabb(Affine2::IDENTITY);
+
+        points = vec![
+            aabb.min,
+            vec2(aabb.max.x, aabb.min.y),
+            aabb.max,
+            vec2(aabb.min.x, aabb.max.y),
+        ];
+    }
+
+    DebugShape::ConvexPolygon { points }
+}
+
 pub(crate) fn make_debug_data(physics: &Physics) -> DebugData {
     let bodies = physics
         .rbd_set
         .arena
         .iter()
         .map(|(_, body)| {
-            let transform = Affine2::from_angle_translation(body.rotation, body.position);
+            let transform = body.transform();
             DebugRigidBody { transform }
         })
         .collect();
@@ -57,17 +137,31 @@ pub(crate) fn make_debug_data(physics: &Physics) -> DebugData {
         .arena
         .iter()
         .map(|(_, collider)| {
-            let radius = match collider.shape.as_ball() {
-                Some(ball) => ball.radius,
-                None => {
-                    println!("Invalid shape, expected ball");
-                    1.0
+            let shape = debug_shape(collider.shape());
+
+            let radius = match &shape {
+                DebugShape::Ball { radius } => *radius,
+                DebugShape::Cuboid { half_extents } => half_extents.length(),
+                DebugShape::ConvexPolygon { points } | DebugShape::Polyline { points, .. } => {
+                    points.iter().map(|p| p.length()).fold(0.0, f32::max)
+                }
+                DebugShape::Capsule {
+                    half_height,
+                    radius,
+                } => half_height + radius,
+                DebugShape::Heightfield { .. } => {
+                    collider.shape.calculate_aabb(Affine2::IDENTITY).size().length() * 0.5
                 }
             };
-    
+
+            // Shapes stay in local space, only the radius accounts for scale.
+            let matrix = collider.absolute_transform.matrix2;
+            let radius = radius * matrix.x_axis.length().max(matrix.y_axis.length());
+
             DebugCollider {
                 transform: collider.absolute_transform,
                 radius,
+                shape,
             }
         })
         .collect();
@@ -89,3 +183,39 @@ pub(crate) fn make_debug_data(physics: &Physics) -> DebugData {
         springs,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A custom shape claiming to be a built-in one.
+    #[derive(Debug)]
+    struct Impostor;
+
+    impl Shape for Impostor {
+        fn shape_type(&self) -> ShapeType {
+            ShapeType::Ball
+        }
+
+        fn calculate_aabb(&self, transform: Affine2) -> AABB {
+            Cuboid::new(Vec2::ONE).calculate_aabb(transform)
+        }
+
+        fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2> {
+            Cuboid::new(Vec2::ONE).support_point(transform, direction)
+        }
+    }
+
+    #[test]
+    fn custom_shapes_are_outlined() {
+        let DebugShape::ConvexPolygon { points } = debug_shape(&Impostor) else {
+            panic!("expected an outline");
+        };
+
+        assert_eq!(points.len(), 4);
+        assert!(matches!(
+            debug_shape(&Ball::new(1.0)),
+            DebugShape::Ball { .. }
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+
+use crate::*;
+
+/// Sent when a collider or rigid body is removed from the world, delivered
+/// at the start of the next step.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RemovalEvent {
+    Collider(ColliderHandle),
+    RigidBody(RigidBodyHandle),
+}
+
+/// Receives the events produced during `Physics::step` and `Physics::fixed_step`.
+///
+/// Every callback defaults to doing nothing.
+pub trait EventHandler {
+    fn handle_collision_event(&mut self, _event: CollisionEvent) {}
+    fn handle_sensor_event(&mut self, _event: SensorEvent) {}
+    fn handle_removal_event(&mut self, _event: RemovalEvent) {}
+}
+
+/// Drops every event.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoopEventHandler;
+
+impl EventHandler for NoopEventHandler {}
+
+/// Forwards events into unbounded channels, they must be drained regularly.
+///
+/// Events of a kind whose receiver was dropped are discarded.
+pub struct ChannelEventHandler {
+    pub collision_send: Sender<CollisionEvent>,
+    pub collision_recv: Receiver<CollisionEvent>,
+    pub sensor_send: Sender<SensorEvent>,
+    pub sensor_recv: Receiver<SensorEvent>,
+    pub removal_send: Sender<RemovalEvent>,
+    pub removal_recv: Receiver<RemovalEvent>,
+}
+
+impl ChannelEventHandler {
+    pub fn new() -> Self {
+        let (collision_send, collision_recv) = std::sync::mpsc::channel();
+        let (sensor_send, sensor_recv) = std::sync::mpsc::channel();
+        let (removal_send, removal_recv) = std::sync::mpsc::channel();
+
+        Self {
+            collision_send,
+            collision_recv,
+            sensor_send,
+            sensor_recv,
+            removal_send,
+            removal_recv,
+        }
+    }
+}
+
+impl Default for ChannelEventHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventHandler for ChannelEventHandler {
+    fn handle_collision_event(&mut self, event: CollisionEvent) {
+        let _ = self.collision_send.send(event);
+    }
+
+    fn handle_sensor_event(&mut self, event: SensorEvent) {
+        let _ = self.sensor_send.send(event);
+    }
+
+    fn handle_removal_event(&mut self, event: RemovalEvent) {
+        let _ = self.removal_send.send(event);
+    }
+}
+
+/// Keeps only the most recent `capacity` events of each kind, older ones
+/// are dropped when nobody reads them.
+#[derive(Clone, Debug)]
+pub struct RingBufferEventHandler {
+    pub capacity: usize,
+    pub collisions: VecDeque<CollisionEvent>,
+    pub sensors: VecDeque<SensorEvent>,
+    pub removals: VecDeque<RemovalEvent>,
+}
+
+impl RingBufferEventHandler {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            collisions: VecDeque::with_capacity(capacity),
+            sensors: VecDeque::with_capacity(capacity),
+            removals: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    fn push<T>(buffer: &mut VecDeque<T>, capacity: usize, event: T) {
+        if capacity == 0 {
+            return;
+        }
+
+        if buffer.len() == capacity {
+            buffer.pop_front();
+        }
+
+        buffer.push_back(event);
+    }
+}
+
+impl EventHandler for RingBufferEventHandler {
+    fn handle_collision_event(&mut self, event: CollisionEvent) {
+        Self::push(&mut self.collisions, self.capacity, event);
+    }
+
+    fn handle_sensor_event(&mut self, event: SensorEvent) {
+        Self::push(&mut self.sensors, self.capacity, event);
+    }
+
+    fn handle_removal_event(&mut self, event: RemovalEvent) {
+        Self::push(&mut self.removals, self.capacity, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_keeps_latest_events() {
+        let mut handler = RingBufferEventHandler::new(2);
+
+        let handles = (0..3)
+            .map(|i| ColliderHandle(Index::from_bits((1 << 32) | i).unwrap()))
+            .collect::<Vec<_>>();
+
+        for handle in handles.iter() {
+            handler.handle_removal_event(RemovalEvent::Collider(*handle));
+        }
+
+        assert_eq!(
+            handler.removals,
+            [
+                RemovalEvent::Collider(handles[1]),
+                RemovalEvent::Collider(handles[2])
+            ]
+        );
+    }
+
+    #[test]
+    fn channels_outlive_dropped_receivers() {
+        let mut handler = ChannelEventHandler::new();
+
+        // Only interested in removals.
+        drop(std::mem::replace(
+            &mut handler.sensor_recv,
+            std::sync::mpsc::channel().1,
+        ));
+
+        let handle = ColliderHandle(Index::from_bits(1 << 32).unwrap());
+
+        handler.handle_sensor_event(SensorEvent {
+            kind: SensorEventKind::Enter,
+            sensor: handle,
+            other: handle,
+        });
+        handler.handle_removal_event(RemovalEvent::Collider(handle));
+
+        assert_eq!(
+            handler.removal_recv.try_recv(),
+            Ok(RemovalEvent::Collider(handle))
+        );
+    }
+}
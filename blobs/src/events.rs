@@ -1,10 +1,13 @@
 use std::collections::VecDeque;
+use std::ops::RangeBounds;
 
 use crate::*;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct TimeData {
+    /// Total time passed into `Physics::step` and `Physics::fixed_step`.
     pub real_time: f64,
+    /// Simulated time, matches `Physics::time`.
     pub unpaused_time: f64,
 }
 
@@ -17,28 +20,96 @@ impl TimeData {
     }
 }
 
-static EVENT_HISTORY: Lazy<AtomicRefCell<EventHistory>> =
-    Lazy::new(|| AtomicRefCell::new(EventHistory::new()));
-
+/// Diagnostics of a single `Physics` world, shared with its body and
+/// collider sets.
 pub struct EventHistory {
     pub events: VecDeque<PhysicsEvent>,
+    /// Oldest events are dropped once this is exceeded.
+    pub capacity: usize,
+    /// Also report every event through the `log` crate.
+    pub forward_to_log: bool,
 }
 
 impl EventHistory {
     pub fn new() -> Self {
-        Self { events: VecDeque::new() }
+        Self::with_capacity(1000)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity,
+            forward_to_log: false,
+        }
     }
 
     pub fn push(&mut self, event: PhysicsEvent) {
+        if self.forward_to_log {
+            log::log!(
+                event.severity.log_level(),
+                "{} (rbd: {:?}, col: {:?}, position: {:?})",
+                event.message,
+                event.rbd_handle,
+                event.col_handle,
+                event.position
+            );
+        }
+
         self.events.push_back(event);
 
-        // Limit queue to 1000 for now.
-        while self.events.len() > 1000 {
+        while self.events.len() > self.capacity {
             self.events.pop_front();
         }
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PhysicsEvent> {
+        self.events.iter()
+    }
+
+    pub fn for_rigid_body(&self, handle: RigidBodyHandle) -> impl Iterator<Item = &PhysicsEvent> {
+        self.iter()
+            .filter(move |event| event.rbd_handle == Some(handle))
+    }
+
+    pub fn for_collider(&self, handle: ColliderHandle) -> impl Iterator<Item = &PhysicsEvent> {
+        self.iter()
+            .filter(move |event| event.col_handle == Some(handle))
+    }
+
+    /// Events at least as severe as `severity`.
+    pub fn with_min_severity(&self, severity: Severity) -> impl Iterator<Item = &PhysicsEvent> {
+        self.iter().filter(move |event| event.severity >= severity)
+    }
+
+    /// Events whose simulated time falls into `range`.
+    pub fn in_time_range(
+        &self,
+        range: impl RangeBounds<f64>,
+    ) -> impl Iterator<Item = &PhysicsEvent> {
+        self.iter()
+            .filter(move |event| range.contains(&event.time_data.unpaused_time))
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
 }
 
+impl Default for EventHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct PhysicsEvent {
     pub time_data: TimeData,
     pub position: Option<Vec2>,
@@ -49,7 +120,8 @@ pub struct PhysicsEvent {
     pub rbd_handle: Option<RigidBodyHandle>,
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Ordered from least to most severe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Severity {
     Trace,
     Debug,
@@ -59,6 +131,50 @@ pub enum Severity {
     Critical,
 }
 
-pub fn push_event(event: PhysicsEvent) {
-    EVENT_HISTORY.borrow_mut().push(event);
+impl Severity {
+    pub fn log_level(self) -> log::Level {
+        match self {
+            Severity::Trace => log::Level::Trace,
+            Severity::Debug => log::Level::Debug,
+            Severity::Info => log::Level::Info,
+            Severity::Warn => log::Level::Warn,
+            Severity::Error | Severity::Critical => log::Level::Error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(time: f64, severity: Severity, rbd_handle: Option<RigidBodyHandle>) -> PhysicsEvent {
+        PhysicsEvent {
+            time_data: TimeData {
+                real_time: time,
+                unpaused_time: time,
+            },
+            position: None,
+            message: "test".into(),
+            severity,
+            col_handle: None,
+            rbd_handle,
+        }
+    }
+
+    #[test]
+    fn event_history_queries() {
+        let body = RigidBodyHandle(Index::from_bits(1 << 32).unwrap());
+        let mut history = EventHistory::with_capacity(3);
+
+        history.push(event(0.0, Severity::Error, None));
+        history.push(event(1.0, Severity::Info, Some(body)));
+        history.push(event(2.0, Severity::Warn, None));
+        history.push(event(3.0, Severity::Critical, Some(body)));
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.for_rigid_body(body).count(), 2);
+        assert_eq!(history.with_min_severity(Severity::Warn).count(), 2);
+        assert_eq!(history.in_time_range(1.5..).count(), 2);
+        assert_eq!(history.in_time_range(..=1.0).count(), 1);
+    }
 }
@@ -0,0 +1,472 @@
+use crate::*;
+
+const GJK_MAX_ITERATIONS: usize = 64;
+const EPA_MAX_ITERATIONS: usize = 64;
+const EPA_TOLERANCE: f32 = 1e-4;
+
+/// Generic contact between two convex shapes using only their support points.
+///
+/// GJK detects the overlap and EPA then finds the penetration normal and
+/// depth. Returns `None` if the shapes are separated or if either of them
+/// doesn't provide support points.
+pub fn contact_gjk(
+    shape_a: &dyn Shape,
+    transform_a: Affine2,
+    shape_b: &dyn Shape,
+    transform_b: Affine2,
+) -> Option<Contact> {
+    contact_gjk_with_margin(shape_a, transform_a, shape_b, transform_b, 0.0)
+}
+
+/// Same as `contact_gjk` with `shape_a` rounded by `margin`.
+pub fn contact_gjk_with_margin(
+    shape_a: &dyn Shape,
+    transform_a: Affine2,
+    shape_b: &dyn Shape,
+    transform_b: Affine2,
+    margin: f32,
+) -> Option<Contact> {
+    let support = |direction: Vec2| -> Option<Vec2> {
+        Some(
+            shape_a.support_point(transform_a, direction)? + direction.normalize_or_zero() * margin
+                - shape_b.support_point(transform_b, -direction)?,
+        )
+    };
+
+    let simplex = gjk_intersection(support)?;
+    let (normal, depth) = epa(support, simplex)?;
+
+    // `normal` points from the origin towards the closest face of the
+    // Minkowski difference, moving `a` against it separates the shapes.
+    let point = shape_a.support_point(transform_a, normal)? + normal * margin;
+
+    Some(Contact::new(-normal, depth, point))
+}
+
+/// Runs GJK on the Minkowski difference described by `support`, returning
+/// a triangle containing the origin if the shapes overlap.
+fn gjk_intersection(support: impl Fn(Vec2) -> Option<Vec2>) -> Option<[Vec2; 3]> {
+    let first = support(Vec2::X)?;
+
+    let mut simplex = vec![first];
+    let mut direction = -first;
+
+    for _ in 0..GJK_MAX_ITERATIONS {
+        if direction.length_squared() < 1e-12 {
+            // The origin lies on the simplex, the shapes are just touching.
+            return None;
+        }
+
+        let point = support(direction)?;
+
+        if point.dot(direction) <= 0.0 {
+            return None;
+        }
+
+        simplex.push(point);
+
+        if update_simplex(&mut simplex, &mut direction) {
+            return Some([simplex[0], simplex[1], simplex[2]]);
+        }
+    }
+
+    None
+}
+
+/// Reduces the simplex to the feature closest to the origin and updates the
+/// search direction. Returns `true` once the triangle encloses the origin.
+fn update_simplex(simplex: &mut Vec<Vec2>, direction: &mut Vec2) -> bool {
+    match simplex.len() {
+        2 => {
+            let (b, a) = (simplex[0], simplex[1]);
+            let ab = b - a;
+            let ao = -a;
+
+            if ab.dot(ao) > 0.0 {
+                let perp = ab.perp();
+                *direction = if perp.dot(ao) >= 0.0 { perp } else { -perp };
+            } else {
+                *simplex = vec![a];
+                *direction = ao;
+            }
+
+            false
+        }
+        3 => {
+            let (c, b, a) = (simplex[0], simplex[1], simplex[2]);
+            let ab = b - a;
+            let ac = c - a;
+            let ao = -a;
+
+            let mut ab_perp = ab.perp();
+            if ab_perp.dot(ac) > 0.0 {
+                ab_perp = -ab_perp;
+            }
+
+            let mut ac_perp = ac.perp();
+            if ac_perp.dot(ab) > 0.0 {
+                ac_perp = -ac_perp;
+            }
+
+            if ab_perp.dot(ao) > 0.0 {
+                *simplex = vec![b, a];
+                *direction = ab_perp;
+                false
+            } else if ac_perp.dot(ao) > 0.0 {
+                *simplex = vec![c, a];
+                *direction = ac_perp;
+                false
+            } else {
+                true
+            }
+        }
+        _ => unreachable!("simplex always has 2 or 3 points when updated"),
+    }
+}
+
+/// Expands the polytope until the face closest to the origin is found,
+/// returning its outward normal and distance.
+fn epa(support: impl Fn(Vec2) -> Option<Vec2>, simplex: [Vec2; 3]) -> Option<(Vec2, f32)> {
+    let [a, b, c] = simplex;
+    let winding = (b - a).perp_dot(c - a);
+
+    if winding.abs() < 1e-12 {
+        return None;
+    }
+
+    // Keep the polytope counter-clockwise so edge normals point outwards.
+    let mut polytope = if winding > 0.0 {
+        vec![a, b, c]
+    } else {
+        vec![a, c, b]
+    };
+
+    let mut best = (Vec2::ZERO, f32::MAX);
+
+    for _ in 0..EPA_MAX_ITERATIONS {
+        let mut closest_edge = 0;
+        best = (Vec2::ZERO, f32::MAX);
+
+        for i in 0..polytope.len() {
+            let v1 = polytope[i];
+            let v2 = polytope[(i + 1) % polytope.len()];
+            let edge = v2 - v1;
+            let normal = vec2(edge.y, -edge.x).normalize_or_zero();
+            let distance = normal.dot(v1);
+
+            if distance < best.1 {
+                best = (normal, distance);
+                closest_edge = i;
+            }
+        }
+
+        let (normal, distance) = best;
+        let point = support(normal)?;
+
+        if point.dot(normal) - distance < EPA_TOLERANCE {
+            return Some(best);
+        }
+
+        polytope.insert(closest_edge + 1, point);
+    }
+
+    Some(best)
+}
+
+/// Closest points between two separated convex shapes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClosestPoints {
+    pub point_a: Vec2,
+    pub point_b: Vec2,
+    /// Points from `point_b` towards `point_a`.
+    pub normal: Vec2,
+    pub distance: f32,
+}
+
+/// Runs GJK on support functions of two convex shapes, returning their
+/// closest points or `None` if they overlap.
+pub fn closest_points_gjk(
+    support_a: impl Fn(Vec2) -> Option<Vec2>,
+    support_b: impl Fn(Vec2) -> Option<Vec2>,
+) -> Option<ClosestPoints> {
+    // Each vertex of the simplex remembers the points it came from, so the
+    // witnesses can be rebuilt from the barycentric weights.
+    let support = |direction: Vec2| -> Option<(Vec2, Vec2)> {
+        Some((support_a(direction)?, support_b(-direction)?))
+    };
+
+    let mut simplex = vec![support(Vec2::X)?];
+    let mut last_distance_squared = f32::MAX;
+
+    for _ in 0..GJK_MAX_ITERATIONS {
+        let weights = reduce_to_closest(&mut simplex)?;
+
+        let closest: Vec2 = simplex
+            .iter()
+            .zip(weights.iter())
+            .map(|((a, b), weight)| (*a - *b) * *weight)
+            .sum();
+
+        let distance_squared = closest.length_squared();
+
+        if distance_squared < 1e-12 {
+            return None;
+        }
+
+        let (a, b) = support(-closest)?;
+        let distance = distance_squared.sqrt();
+
+        // Rounding in the support points can make GJK cycle without
+        // getting closer, which is as converged as it gets.
+        let stalled = distance_squared >= last_distance_squared;
+        last_distance_squared = distance_squared;
+
+        // Otherwise stop once no support point gets meaningfully closer to
+        // the origin. The tolerance is absolute since f32 can't resolve tiny
+        // relative gains for shapes nearly touching far from the origin.
+        if distance - closest.dot(a - b) / distance <= 1e-5 || stalled {
+            let point_a = simplex
+                .iter()
+                .zip(weights.iter())
+                .map(|((a, _), w)| *a * *w)
+                .sum();
+            let point_b = simplex
+                .iter()
+                .zip(weights.iter())
+                .map(|((_, b), w)| *b * *w)
+                .sum();
+
+            return Some(ClosestPoints {
+                point_a,
+                point_b,
+                normal: closest / distance,
+                distance,
+            });
+        }
+
+        simplex.push((a, b));
+    }
+
+    None
+}
+
+/// Projects `point` onto a convex shape described by its support points.
+///
+/// Points outside use the GJK distance. Points inside stay where they are if
+/// `solid`, otherwise EPA finds the nearest way out.
+pub fn project_point_gjk(
+    support: impl Fn(Vec2) -> Option<Vec2>,
+    point: Vec2,
+    solid: bool,
+) -> Option<PointProjection> {
+    support(Vec2::X)?;
+
+    if let Some(closest) = closest_points_gjk(&support, |_| Some(point)) {
+        return Some(PointProjection::new(closest.point_a, false));
+    }
+
+    if solid {
+        return Some(PointProjection::new(point, true));
+    }
+
+    // The shape as seen from the point contains the origin, its closest
+    // face leads back to the boundary.
+    let relative = |direction: Vec2| Some(support(direction)? - point);
+    let nearest = gjk_intersection(relative)
+        .and_then(|simplex| epa(relative, simplex))
+        .map_or(point, |(normal, distance)| point + normal * distance);
+
+    Some(PointProjection::new(nearest, true))
+}
+
+/// Reduces the simplex to the feature closest to the origin, returning the
+/// barycentric weights of its closest point or `None` if the origin is
+/// inside.
+fn reduce_to_closest(simplex: &mut Vec<(Vec2, Vec2)>) -> Option<Vec<f32>> {
+    let point = |i: usize, simplex: &[(Vec2, Vec2)]| simplex[i].0 - simplex[i].1;
+
+    match simplex.len() {
+        1 => Some(vec![1.0]),
+        2 => {
+            let (p, q) = (point(0, simplex), point(1, simplex));
+            let edge = q - p;
+            let t = (-p.dot(edge) / edge.length_squared().max(1e-12)).clamp(0.0, 1.0);
+
+            if t <= 0.0 {
+                simplex.remove(1);
+                Some(vec![1.0])
+            } else if t >= 1.0 {
+                simplex.remove(0);
+                Some(vec![1.0])
+            } else {
+                Some(vec![1.0 - t, t])
+            }
+        }
+        3 => {
+            let [a, b, c] = [0, 1, 2].map(|i| point(i, simplex));
+            let area = (b - a).perp_dot(c - a);
+
+            // The origin is on the inner side of every edge, degenerate
+            // triangles are handled through their edges.
+            let inside = area.abs() > 1e-12
+                && [(a, b), (b, c), (c, a)]
+                    .iter()
+                    .all(|(p, q)| (*q - *p).perp_dot(-*p) * area >= 0.0);
+
+            if inside {
+                return None;
+            }
+
+            // The closest point of a triangle not containing the origin lies
+            // on one of its edges.
+            let best_edge = [(0, 1), (1, 2), (2, 0)]
+                .into_iter()
+                .map(|(i, j)| {
+                    let (p, q) = (point(i, simplex), point(j, simplex));
+                    let edge = q - p;
+                    let t = (-p.dot(edge) / edge.length_squared().max(1e-12)).clamp(0.0, 1.0);
+                    ((i, j), (p + edge * t).length_squared())
+                })
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+                .unwrap()
+                .0;
+
+            *simplex = vec![simplex[best_edge.0], simplex[best_edge.1]];
+            reduce_to_closest(simplex)
+        }
+        _ => unreachable!("simplex always has 1 to 3 points"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// A shape only known through its support points.
+    #[derive(Debug)]
+    struct Diamond {
+        size: f32,
+    }
+
+    impl Shape for Diamond {
+        fn calculate_aabb(&self, transform: Affine2) -> AABB {
+            let mut aabb = AABB::new(transform.translation, transform.translation);
+
+            for direction in [Vec2::X, Vec2::Y, -Vec2::X, -Vec2::Y] {
+                aabb.expand_to_include_point(self.support_point(transform, direction).unwrap());
+            }
+
+            aabb
+        }
+
+        fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2> {
+            let local = transform.matrix2.transpose() * direction;
+
+            let corner = if local.x.abs() > local.y.abs() {
+                vec2(self.size * local.x.signum(), 0.0)
+            } else {
+                vec2(0.0, self.size * local.y.signum())
+            };
+
+            Some(transform.transform_point2(corner))
+        }
+    }
+
+    #[test]
+    fn gjk_matches_sat_for_boxes() {
+        let cuboid = Cuboid::new(vec2(1.0, 1.0));
+        let transform_a = Affine2::from_angle_translation(0.3, vec2(0.2, 1.7));
+
+        let sat = contact_shapes(&cuboid, transform_a, &cuboid, Affine2::IDENTITY).unwrap();
+        let gjk = contact_gjk(&cuboid, transform_a, &cuboid, Affine2::IDENTITY).unwrap();
+
+        assert_relative_eq!(sat.normal, gjk.normal, epsilon = 1e-3);
+        assert_relative_eq!(sat.depth, gjk.depth, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn custom_shape_collides() {
+        let diamond = Diamond { size: 1.0 };
+        let ball = Ball::new(0.5);
+
+        assert_eq!(diamond.shape_type(), ShapeType::Custom);
+
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(0.0, 1.4)),
+            &diamond,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        // EPA only approximates the curved side of the ball.
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-2);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-3);
+
+        let cuboid = Cuboid::new(vec2(1.0, 1.0));
+
+        let contact = contact_shapes(
+            &diamond,
+            Affine2::IDENTITY,
+            &cuboid,
+            Affine2::from_translation(vec2(1.9, 0.0)),
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(-1.0, 0.0), epsilon = 1e-3);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-3);
+
+        assert!(contact_shapes(
+            &diamond,
+            Affine2::IDENTITY,
+            &cuboid,
+            Affine2::from_translation(vec2(1.6, 1.6)),
+        )
+        .is_none());
+
+        // A margin rounds the diamond, so the cuboid now touches it.
+        let contact = contact_shapes_with_margin(
+            &diamond,
+            Affine2::IDENTITY,
+            &cuboid,
+            Affine2::from_translation(vec2(2.05, 0.0)),
+            0.1,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(-1.0, 0.0), epsilon = 1e-2);
+        assert_relative_eq!(contact.depth, 0.05, epsilon = 1e-3);
+
+        // Non-convex shapes without support points never reach GJK.
+        let polyline = Polyline::new(vec![vec2(-1.0, 0.0), vec2(1.0, 0.0)]);
+        assert!(
+            contact_shapes(&diamond, Affine2::IDENTITY, &polyline, Affine2::IDENTITY).is_none()
+        );
+    }
+
+    #[test]
+    fn closest_points_between_shapes() {
+        let ball = Ball::new(0.5);
+        let cuboid = Cuboid::new(vec2(1.0, 1.0));
+        let ball_at = Affine2::from_translation(vec2(3.0, 0.5));
+
+        let closest = closest_points_gjk(
+            |d| ball.support_point(ball_at, d),
+            |d| cuboid.support_point(Affine2::IDENTITY, d),
+        )
+        .unwrap();
+
+        assert_relative_eq!(closest.distance, 1.5, epsilon = 1e-3);
+        assert_relative_eq!(closest.normal, vec2(1.0, 0.0), epsilon = 1e-3);
+        assert_relative_eq!(closest.point_a, vec2(2.5, 0.5), epsilon = 1e-2);
+        assert_relative_eq!(closest.point_b.x, 1.0, epsilon = 1e-3);
+
+        let overlapping = Affine2::from_translation(vec2(1.2, 0.0));
+        assert!(closest_points_gjk(
+            |d| ball.support_point(overlapping, d),
+            |d| cuboid.support_point(Affine2::IDENTITY, d),
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::*;
+
+/// A candidate pair handed to `PhysicsHooks`, after the group, shared
+/// parent and static checks passed.
+pub struct PairContext<'a> {
+    pub rbd_set: &'a RigidBodySet,
+    pub col_set: &'a ColliderSet,
+
+    pub collider_a: ColliderHandle,
+    pub collider_b: ColliderHandle,
+    pub rigid_body_a: RigidBodyHandle,
+    pub rigid_body_b: RigidBodyHandle,
+
+    /// Simulation time at the start of the current step.
+    pub time: f64,
+}
+
+/// Contact of a pair before it's solved, see `PhysicsHooks::modify_contact`.
+#[derive(Copy, Clone, Debug)]
+pub struct ContactModification {
+    /// Points from the second collider towards the first one.
+    pub normal: Vec2,
+    pub depth: f32,
+    /// Multiplies the positional correction, `1.0` by default.
+    pub correction_scale: f32,
+    /// Skips the correction while still reporting the contact, e.g. for
+    /// one-way platforms.
+    pub pass_through: bool,
+}
+
+impl ContactModification {
+    pub fn new(contact: &Contact) -> Self {
+        Self {
+            normal: contact.normal,
+            depth: contact.depth,
+            correction_scale: 1.0,
+            pass_through: false,
+        }
+    }
+}
+
+/// User rules consulted by `Physics` for every candidate pair.
+pub trait PhysicsHooks {
+    /// Returning `false` ignores the pair for this substep.
+    fn filter_contact_pair(&mut self, _pair: &PairContext) -> bool {
+        true
+    }
+
+    /// Called for touching pairs before their correction is applied.
+    fn modify_contact(&mut self, _pair: &PairContext, _contact: &mut ContactModification) {}
+}
+
+impl PhysicsHooks for () {}
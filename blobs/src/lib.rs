@@ -1,4 +1,5 @@
 use std::{
+    cell::{Cell, RefCell},
     fmt::Debug,
     sync::mpsc::{Receiver, Sender},
 };
@@ -17,66 +18,58 @@ use glam::*;
 use itertools::Itertools;
 use thunderdome::{Arena, Index};
 
+mod broad_phase;
+mod bvh;
 mod collider;
+mod contact_pairs;
 mod debug;
+mod event_handler;
 mod events;
+mod gjk;
 mod groups;
+mod hooks;
 mod joints;
+mod narrow_phase;
 mod physics;
+mod point_query;
 mod query_filter;
+mod query_pipeline;
+mod ray;
 mod rigid_body;
+mod sap;
+mod shape_cast;
+mod shapes;
 mod spatial;
 mod springs;
 mod tests;
 
+pub use crate::broad_phase::*;
+pub use crate::bvh::*;
 pub use crate::collider::*;
+pub use crate::contact_pairs::*;
 pub use crate::debug::*;
+pub use crate::event_handler::*;
 pub use crate::events::*;
+pub use crate::gjk::*;
 pub use crate::groups::*;
+pub use crate::hooks::*;
 pub use crate::joints::*;
+pub use crate::narrow_phase::*;
 pub use crate::physics::*;
+pub use crate::point_query::*;
 pub use crate::query_filter::*;
+pub use crate::query_pipeline::*;
+pub use crate::ray::*;
 pub use crate::rigid_body::*;
+pub use crate::sap::*;
+pub use crate::shape_cast::*;
+pub use crate::shapes::*;
 pub use crate::spatial::*;
 pub use crate::springs::*;
 
 #[derive(Copy, Clone, Debug)]
 pub struct Velocity(pub Vec2);
 
-#[derive(Clone, Debug)]
-pub struct Ball {
-    pub radius: f32,
-}
-
-impl Shape for Ball {
-    fn as_ball(&self) -> Option<&Ball> {
-        Some(self)
-    }
-
-    fn as_cuboid(&self) -> Option<&Cuboid> {
-        None
-    }
-
-    fn calculate_aabb(&self, transform: Affine2) -> AABB {
-        let min = transform.translation - vec2(self.radius, self.radius);
-        let max = transform.translation + vec2(self.radius, self.radius);
-
-        AABB::new(min, max)
-    }
-}
-
-impl Ball {
-    pub fn new(radius: f32) -> Self {
-        Self { radius }
-    }
-}
-
-pub trait Shape: 'static + Debug {
-    fn as_ball(&self) -> Option<&Ball>;
-    fn as_cuboid(&self) -> Option<&Cuboid>;
-    fn calculate_aabb(&self, transform: Affine2) -> AABB;
-}
-
 #[derive(Debug, Clone, Copy)]
 pub struct AABB {
     pub min: Vec2,
@@ -132,6 +125,18 @@ impl AABB {
             && self.max.y >= other.min.y
     }
 
+    pub fn contains_aabb(&self, other: &AABB) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && self.max.x >= other.max.x
+            && self.max.y >= other.max.y
+    }
+
+    /// Copy grown by `amount` on every side.
+    pub fn grown(&self, amount: f32) -> AABB {
+        AABB::new(self.min - Vec2::splat(amount), self.max + Vec2::splat(amount))
+    }
+
     pub fn expand_to_include_point(&mut self, point: Vec2) {
         self.min = self.min.min(point);
         self.max = self.max.max(point);
@@ -143,46 +148,90 @@ impl AABB {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CollisionEventKind {
+    /// The colliders started touching during the step.
+    Started,
+    /// The colliders touched during the previous step but no longer do.
+    Stopped,
+    /// The colliders kept touching since an earlier step. Only used for the
+    /// data stored in `Physics::contact_pairs`, never sent as an event.
+    Ongoing,
+}
+
+/// Sent once when a pair of colliders starts or stops touching.
+///
+/// Data of ongoing contacts is available through `Physics::contact_pairs`.
 #[derive(Copy, Clone, Debug)]
 pub struct CollisionEvent {
+    pub kind: CollisionEventKind,
+
     pub col_handle_a: ColliderHandle,
     pub col_handle_b: ColliderHandle,
 
     pub impact_vel_a: Vec2,
     pub impact_vel_b: Vec2,
-}
 
-pub struct Rotation {}
+    pub manifold: ContactManifold,
+}
 
-impl Rotation {
-    pub fn angle(&self) -> f32 {
-        todo!()
-    }
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SensorEventKind {
+    Enter,
+    Exit,
 }
 
-pub struct Cuboid {
-    pub half_extents: Vec2,
+/// Sent once when a collider starts or stops overlapping a sensor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SensorEvent {
+    pub kind: SensorEventKind,
+    /// If both colliders are sensors, this is the first one of the pair.
+    pub sensor: ColliderHandle,
+    pub other: ColliderHandle,
 }
 
-pub struct QueryPipeline {
-    #[allow(dead_code)]
-    time_data: Rc<TimeData>,
+/// Where and how hard two colliders hit, from the first collider's point
+/// of view.
+#[derive(Copy, Clone, Debug)]
+pub struct ContactManifold {
+    /// Points from the second collider towards the first one.
+    pub normal: Vec2,
+    pub depth: f32,
+    /// World space contact points, only the first `point_count` are valid.
+    pub points: [Vec2; 2],
+    pub point_count: usize,
+    /// Velocity of the first body relative to the second along `normal`,
+    /// negative while they approach each other.
+    pub relative_normal_velocity: f32,
+    /// Positional correction applied to each body, zero for sensors and
+    /// static bodies.
+    pub correction_a: Vec2,
+    pub correction_b: Vec2,
 }
 
-impl QueryPipeline {
-    pub fn new(time_data: Rc<TimeData>) -> Self {
-        Self { time_data }
+impl ContactManifold {
+    pub fn new(contact: Contact, relative_velocity: Vec2) -> Self {
+        Self {
+            normal: contact.normal,
+            depth: contact.depth,
+            points: contact.points,
+            point_count: contact.point_count,
+            relative_normal_velocity: relative_velocity.dot(contact.normal),
+            correction_a: Vec2::ZERO,
+            correction_b: Vec2::ZERO,
+        }
     }
 
-    pub fn intersection_with_shape(
-        &self,
-        _rbd_set: &RigidBodySet,
-        _col_set: &ColliderSet,
-        _position: &Vec2,
-        _shape: &dyn Shape,
-        _filter: QueryFilter,
-    ) -> Option<ColliderHandle> {
-        None
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.point_count]
+    }
+}
+
+pub struct Rotation {}
+
+impl Rotation {
+    pub fn angle(&self) -> f32 {
+        todo!()
     }
 }
 
@@ -229,18 +278,36 @@ impl<A, B> ZipTuple<A, B> for (Option<A>, Option<B>) {
 pub trait AffineExtensions {
     fn angle(&self) -> f32;
     fn angle_dir(&self) -> Vec2;
+    /// Length scale of the transform, the square root of its area scale.
+    fn scale_factor(&self) -> f32;
+    /// Whether the transform preserves angles, scaling both axes alike.
+    fn has_uniform_scale(&self) -> bool;
 }
 
 impl AffineExtensions for Affine2 {
     fn angle(&self) -> f32 {
-        // NOTE: This breaks if the transform has a scale or shear component
-        let up = vec2(0.0, 1.0);
-        // up.angle_between(self.transform_vector2(up))
-        self.transform_vector2(up).angle_between(up)
+        // NOTE: Handles positive scale, but breaks under shear or mirroring
+        let x_axis = self.matrix2.x_axis;
+        x_axis.y.atan2(x_axis.x)
     }
 
     fn angle_dir(&self) -> Vec2 {
         let angle = self.angle();
         Vec2::from_angle(angle)
     }
+
+    fn scale_factor(&self) -> f32 {
+        self.matrix2.determinant().abs().sqrt()
+    }
+
+    fn has_uniform_scale(&self) -> bool {
+        let x = self.matrix2.x_axis.length_squared();
+        let y = self.matrix2.y_axis.length_squared();
+
+        // Axes of equal length can still be sheared, e.g. a rotated
+        // collider offset on a body with a non-uniform scale.
+        let skew = self.matrix2.x_axis.dot(self.matrix2.y_axis);
+
+        (x - y).abs() <= 1e-4 * x.max(y) && skew.abs() <= 1e-4 * x.max(y)
+    }
 }
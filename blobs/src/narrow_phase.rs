@@ -0,0 +1,1232 @@
+use crate::*;
+
+/// Result of a narrow phase test between two shapes.
+///
+/// `normal` points from the second shape towards the first one, so moving
+/// the first shape by `normal * depth` separates the pair.
+#[derive(Copy, Clone, Debug)]
+pub struct Contact {
+    pub normal: Vec2,
+    pub depth: f32,
+    /// World space contact points, only the first `point_count` are valid.
+    pub points: [Vec2; 2],
+    pub point_count: usize,
+}
+
+impl Contact {
+    pub fn new(normal: Vec2, depth: f32, point: Vec2) -> Self {
+        Self {
+            normal,
+            depth,
+            points: [point, Vec2::ZERO],
+            point_count: 1,
+        }
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.point_count]
+    }
+
+    /// The same contact as seen from the other shape.
+    pub fn flipped(self) -> Self {
+        Self {
+            normal: -self.normal,
+            ..self
+        }
+    }
+}
+
+/// Computes the contact between two shapes placed at the given transforms.
+///
+/// Pairs of built-in shapes with a specialised routine in `contact_dispatch`
+/// use it, any other pair of convex shapes falls back to GJK/EPA. Returns
+/// `None` if the shapes don't overlap or if the pair can't be handled.
+pub fn contact_shapes(
+    shape_a: &dyn Shape,
+    transform_a: Affine2,
+    shape_b: &dyn Shape,
+    transform_b: Affine2,
+) -> Option<Contact> {
+    // Balls and capsules turn into ellipses under a non-uniform scale, which
+    // only the generic path handles exactly. Without support points the
+    // specialised routines still run with an averaged radius.
+    if (!keeps_proportions(shape_a, transform_a) || !keeps_proportions(shape_b, transform_b))
+        && shape_a.support_point(transform_a, Vec2::X).is_some()
+        && shape_b.support_point(transform_b, Vec2::X).is_some()
+    {
+        return contact_gjk(shape_a, transform_a, shape_b, transform_b);
+    }
+
+    if let (Some(type_a), Some(type_b)) = (builtin_type(shape_a), builtin_type(shape_b)) {
+        if let Some(contact_fn) = contact_dispatch(type_a, type_b) {
+            return contact_fn(shape_a, transform_a, shape_b, transform_b);
+        }
+
+        if let Some(contact_fn) = contact_dispatch(type_b, type_a) {
+            return contact_fn(shape_b, transform_b, shape_a, transform_a).map(Contact::flipped);
+        }
+    }
+
+    contact_gjk(shape_a, transform_a, shape_b, transform_b)
+}
+
+/// Same as `contact_shapes` but with both shapes inflated by a combined
+/// contact `margin`.
+///
+/// Balls simply grow by the margin. Other pairs go through GJK/EPA on the
+/// rounded shapes, so polylines and heightfields only honour the margin
+/// against balls.
+pub fn contact_shapes_with_margin(
+    shape_a: &dyn Shape,
+    transform_a: Affine2,
+    shape_b: &dyn Shape,
+    transform_b: Affine2,
+    margin: f32,
+) -> Option<Contact> {
+    if margin <= 0.0 {
+        return contact_shapes(shape_a, transform_a, shape_b, transform_b);
+    }
+
+    if let Some(ball) = shape_a.as_ball() {
+        let inflated = Ball::new(ball.radius + margin / transform_a.scale_factor());
+        return contact_shapes(&inflated, transform_a, shape_b, transform_b);
+    }
+
+    if let Some(ball) = shape_b.as_ball() {
+        let inflated = Ball::new(ball.radius + margin / transform_b.scale_factor());
+        return contact_shapes(shape_a, transform_a, &inflated, transform_b);
+    }
+
+    // Terrain has no support points for GJK, its routines go without margin.
+    if shape_a.support_point(transform_a, Vec2::X).is_none()
+        || shape_b.support_point(transform_b, Vec2::X).is_none()
+    {
+        return contact_shapes(shape_a, transform_a, shape_b, transform_b);
+    }
+
+    contact_gjk_with_margin(shape_a, transform_a, shape_b, transform_b, margin)
+}
+
+fn keeps_proportions(shape: &dyn Shape, transform: Affine2) -> bool {
+    !matches!(
+        builtin_type(shape),
+        Some(ShapeType::Ball | ShapeType::Capsule)
+    ) || transform.has_uniform_scale()
+}
+
+/// Type of a built-in shape found by downcasting. Custom shapes may claim a
+/// built-in `shape_type`, but the specialised routines can't read them.
+fn builtin_type(shape: &dyn Shape) -> Option<ShapeType> {
+    let shape_type = if shape.as_ball().is_some() {
+        ShapeType::Ball
+    } else if shape.as_cuboid().is_some() {
+        ShapeType::Cuboid
+    } else if shape.as_convex_polygon().is_some() {
+        ShapeType::ConvexPolygon
+    } else if shape.as_capsule().is_some() {
+        ShapeType::Capsule
+    } else if shape.as_polyline().is_some() {
+        ShapeType::Polyline
+    } else if shape.as_heightfield().is_some() {
+        ShapeType::Heightfield
+    } else {
+        return None;
+    };
+
+    Some(shape_type)
+}
+
+/// Radius of a ball in world space.
+fn ball_radius(shape: &dyn Shape, transform: Affine2) -> Option<f32> {
+    Some(shape.as_ball()?.radius * transform.scale_factor())
+}
+
+/// Specialised contact routine for a pair of shapes of known types.
+pub type ContactFn = fn(&dyn Shape, Affine2, &dyn Shape, Affine2) -> Option<Contact>;
+
+/// Table of the specialised contact routines.
+///
+/// Only one order of each pair is listed, `contact_shapes` also tries the
+/// pair swapped and flips the result.
+pub fn contact_dispatch(type_a: ShapeType, type_b: ShapeType) -> Option<ContactFn> {
+    use ShapeType::*;
+
+    let contact_fn: ContactFn = match (type_a, type_b) {
+        (Ball, Ball) => |a, ta, b, tb| {
+            contact_ball_ball(
+                ta.translation,
+                ball_radius(a, ta)?,
+                tb.translation,
+                ball_radius(b, tb)?,
+            )
+        },
+        (Cuboid, Ball) => |a, ta, b, tb| {
+            contact_cuboid_ball(a.as_cuboid()?, ta, tb.translation, ball_radius(b, tb)?)
+        },
+        (ConvexPolygon, Ball) => |a, ta, b, tb| {
+            contact_polygon_ball(&world_polygon(a, ta)?, tb.translation, ball_radius(b, tb)?)
+        },
+        (Cuboid | ConvexPolygon, Cuboid | ConvexPolygon) => {
+            |a, ta, b, tb| contact_polygons(&world_polygon(a, ta)?, &world_polygon(b, tb)?)
+        }
+        (Capsule, Ball) => |a, ta, b, tb| {
+            contact_capsule_ball(a.as_capsule()?, ta, tb.translation, ball_radius(b, tb)?)
+        },
+        (Capsule, Capsule) => {
+            |a, ta, b, tb| contact_capsule_capsule(a.as_capsule()?, ta, b.as_capsule()?, tb)
+        }
+        (Cuboid | ConvexPolygon, Capsule) => {
+            |a, ta, b, tb| contact_polygon_capsule(&world_polygon(a, ta)?, b.as_capsule()?, tb)
+        }
+        (Polyline, Ball) => |a, ta, b, tb| {
+            contact_polyline_ball(a.as_polyline()?, ta, tb.translation, ball_radius(b, tb)?)
+        },
+        (Polyline, Cuboid | ConvexPolygon | Capsule) => {
+            |a, ta, b, tb| contact_polyline_convex(a.as_polyline()?, ta, b, tb)
+        }
+        (Heightfield, Ball) => |a, ta, b, tb| {
+            contact_heightfield_ball(a.as_heightfield()?, ta, tb.translation, ball_radius(b, tb)?)
+        },
+        (Heightfield, Cuboid | ConvexPolygon | Capsule) => {
+            |a, ta, b, tb| contact_heightfield_convex(a.as_heightfield()?, ta, b, tb)
+        }
+        _ => return None,
+    };
+
+    Some(contact_fn)
+}
+
+pub fn contact_ball_ball(
+    center_a: Vec2,
+    radius_a: f32,
+    center_b: Vec2,
+    radius_b: f32,
+) -> Option<Contact> {
+    let axis = center_a - center_b;
+    let distance = axis.length();
+    let min_dist = radius_a + radius_b;
+
+    if distance >= min_dist {
+        return None;
+    }
+
+    // Perfectly overlapping centers have no meaningful axis, pick any.
+    let normal = if distance < 1e-6 {
+        Vec2::X
+    } else {
+        axis / distance
+    };
+
+    Some(Contact::new(
+        normal,
+        min_dist - distance,
+        center_b + normal * radius_b,
+    ))
+}
+
+/// Contact between a cuboid (first shape) and a ball (second shape).
+pub fn contact_cuboid_ball(
+    cuboid: &Cuboid,
+    transform: Affine2,
+    center: Vec2,
+    radius: f32,
+) -> Option<Contact> {
+    // Clamping in local space only finds the nearest point if the transform
+    // preserves angles, a sheared box is handled as a world space polygon.
+    if !transform.has_uniform_scale() {
+        return contact_polygon_ball(&cuboid.world_vertices(transform), center, radius);
+    }
+
+    let inverse = transform.inverse();
+    let local_center = inverse.transform_point2(center);
+    let h = cuboid.half_extents;
+    let clamped = local_center.clamp(-h, h);
+
+    if clamped != local_center {
+        let closest = transform.transform_point2(clamped);
+        let axis = center - closest;
+        let distance = axis.length();
+
+        if distance >= radius {
+            return None;
+        }
+
+        return Some(Contact::new(-axis / distance, radius - distance, closest));
+    }
+
+    // The center is inside the box, push it out through the nearest face.
+    let axis_scale = vec2(
+        transform.matrix2.x_axis.length(),
+        transform.matrix2.y_axis.length(),
+    );
+    let gap = (h - local_center.abs()) * axis_scale;
+
+    let (local_normal, local_surface) = if gap.x < gap.y {
+        let sign = local_center.x.signum();
+        (vec2(sign, 0.0), vec2(sign * h.x, local_center.y))
+    } else {
+        let sign = local_center.y.signum();
+        (vec2(0.0, sign), vec2(local_center.x, sign * h.y))
+    };
+
+    let surface = transform.transform_point2(local_surface);
+    let outward = (inverse.matrix2.transpose() * local_normal).normalize();
+    let depth = (surface - center).dot(outward) + radius;
+
+    Some(Contact::new(-outward, depth, surface))
+}
+
+/// Contact between a polyline (first shape) and a ball (second shape).
+///
+/// Only the single closest feature of the whole chain is used, which makes
+/// the normal at a shared vertex point from the vertex towards the ball
+/// instead of reporting a ghost collision with the neighbouring segment.
+pub fn contact_polyline_ball(
+    polyline: &Polyline,
+    transform: Affine2,
+    center: Vec2,
+    radius: f32,
+) -> Option<Contact> {
+    let vertices = polyline.world_vertices(transform);
+
+    let mut closest: Option<(Vec2, Vec2, f32)> = None;
+
+    for segment in vertices.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let front = (b - a).perp().normalize_or_zero();
+
+        // One-sided segments ignore balls that are behind them.
+        if polyline.one_sided && front.dot(center - a) < 0.0 {
+            continue;
+        }
+
+        let point = closest_point_on_segment(a, b, center);
+        let distance_squared = point.distance_squared(center);
+
+        let is_closer = match closest {
+            Some((_, _, best)) => distance_squared < best,
+            None => true,
+        };
+
+        if is_closer {
+            closest = Some((point, front, distance_squared));
+        }
+    }
+
+    let (point, front, distance_squared) = closest?;
+
+    if distance_squared >= radius * radius {
+        return None;
+    }
+
+    let distance = distance_squared.sqrt();
+
+    let normal = if distance < 1e-6 {
+        front
+    } else {
+        (center - point) / distance
+    };
+
+    Some(Contact::new(-normal, radius - distance, point))
+}
+
+/// Contact between a polyline (first shape) and a cuboid, convex polygon or
+/// capsule (second shape), keeping the deepest contact of any segment.
+pub fn contact_polyline_convex(
+    polyline: &Polyline,
+    transform: Affine2,
+    shape: &dyn Shape,
+    shape_transform: Affine2,
+) -> Option<Contact> {
+    let aabb = shape.calculate_aabb(shape_transform);
+    let center = shape_transform.translation;
+
+    polyline
+        .world_vertices(transform)
+        .windows(2)
+        .filter(|segment| AABB::from_two_points(segment[0], segment[1]).intersects(&aabb))
+        // One-sided segments ignore shapes centered behind them.
+        .filter(|segment| {
+            !polyline.one_sided || (segment[1] - segment[0]).perp().dot(center - segment[0]) >= 0.0
+        })
+        .filter_map(|segment| contact_polygon_convex(segment, shape, shape_transform))
+        .max_by(|a, b| a.depth.total_cmp(&b.depth))
+}
+
+/// Contact between a heightfield (first shape) and a ball (second shape).
+///
+/// Only the few cells under the ball are visited, so the cost doesn't depend
+/// on the number of samples.
+pub fn contact_heightfield_ball(
+    heightfield: &Heightfield,
+    transform: Affine2,
+    center: Vec2,
+    radius: f32,
+) -> Option<Contact> {
+    let local_center = transform.inverse().transform_point2(center);
+    // Extent of the ball along the heightfield's local X axis.
+    let local_radius = radius / transform.matrix2.x_axis.length();
+
+    let mut closest: Option<(Vec2, Vec2, f32)> = None;
+
+    for i in
+        heightfield.cells_in_range(local_center.x - local_radius, local_center.x + local_radius)
+    {
+        let a = transform.transform_point2(heightfield.local_point(i));
+        let b = transform.transform_point2(heightfield.local_point(i + 1));
+        let up = (b - a).perp().normalize_or_zero();
+
+        let edge = b - a;
+        let t = (center - a).dot(edge) / edge.length_squared();
+        let height = up.dot(center - a);
+
+        // Everything below the surface is solid, so a center under the
+        // cell is pushed straight up regardless of how deep it is.
+        if (0.0..=1.0).contains(&t) && height < 0.0 {
+            return Some(Contact::new(-up, radius - height, center - up * height));
+        }
+
+        let point = closest_point_on_segment(a, b, center);
+        let distance_squared = point.distance_squared(center);
+
+        let is_closer = match closest {
+            Some((_, _, best)) => distance_squared < best,
+            None => true,
+        };
+
+        if is_closer {
+            closest = Some((point, up, distance_squared));
+        }
+    }
+
+    let (point, up, distance_squared) = closest?;
+
+    if distance_squared >= radius * radius {
+        return None;
+    }
+
+    let distance = distance_squared.sqrt();
+
+    let normal = if distance < 1e-6 {
+        up
+    } else {
+        (center - point) / distance
+    };
+
+    Some(Contact::new(-normal, radius - distance, point))
+}
+
+/// Contact between a heightfield (first shape) and a cuboid, convex polygon
+/// or capsule (second shape), keeping the deepest contact of any cell.
+pub fn contact_heightfield_convex(
+    heightfield: &Heightfield,
+    transform: Affine2,
+    shape: &dyn Shape,
+    shape_transform: Affine2,
+) -> Option<Contact> {
+    let inverse = transform.inverse();
+    let aabb = shape.calculate_aabb(shape_transform);
+    let corners = [
+        aabb.min,
+        vec2(aabb.max.x, aabb.min.y),
+        aabb.max,
+        vec2(aabb.min.x, aabb.max.y),
+    ]
+    .map(|corner| inverse.transform_point2(corner).x);
+
+    let min_x = corners.into_iter().reduce(f32::min)?;
+    let max_x = corners.into_iter().reduce(f32::max)?;
+
+    heightfield
+        .cells_in_range(min_x, max_x)
+        .filter_map(|i| {
+            let a = transform.transform_point2(heightfield.local_point(i));
+            let b = transform.transform_point2(heightfield.local_point(i + 1));
+            let up = (b - a).perp().normalize_or_zero();
+
+            let contact = contact_polygon_convex(&[a, b], shape, shape_transform);
+
+            if contact.is_some_and(|contact| contact.normal.dot(up) < 0.0) {
+                return contact;
+            }
+
+            // Everything below the surface is solid, so a shape reaching
+            // under the cell is pushed straight up regardless of how deep
+            // it is.
+            let deepest = shape.support_point(shape_transform, -up)?;
+            let edge = b - a;
+            let t = (deepest - a).dot(edge) / edge.length_squared();
+            let height = up.dot(deepest - a);
+
+            ((0.0..=1.0).contains(&t) && height < 0.0)
+                .then(|| Contact::new(-up, -height, deepest - up * height))
+        })
+        .max_by(|a, b| a.depth.total_cmp(&b.depth))
+}
+
+/// Contact between a convex polygon (first shape) given as world space
+/// vertices in counter-clockwise order and a ball (second shape).
+pub fn contact_polygon_ball(vertices: &[Vec2], center: Vec2, radius: f32) -> Option<Contact> {
+    let mut max_separation = f32::MIN;
+    let mut face = 0;
+
+    for i in 0..vertices.len() {
+        let normal = edge_normal(vertices[i], vertices[(i + 1) % vertices.len()]);
+        let separation = normal.dot(center - vertices[i]);
+
+        if separation > max_separation {
+            max_separation = separation;
+            face = i;
+        }
+    }
+
+    if max_separation > radius {
+        return None;
+    }
+
+    if max_separation <= 0.0 {
+        // The center is inside the polygon, push it out through the closest face.
+        let normal = edge_normal(vertices[face], vertices[(face + 1) % vertices.len()]);
+
+        return Some(Contact::new(
+            -normal,
+            radius - max_separation,
+            center - normal * max_separation,
+        ));
+    }
+
+    let closest = (0..vertices.len())
+        .map(|i| closest_point_on_segment(vertices[i], vertices[(i + 1) % vertices.len()], center))
+        .min_by(|a, b| {
+            a.distance_squared(center)
+                .total_cmp(&b.distance_squared(center))
+        })
+        .unwrap();
+
+    let axis = center - closest;
+    let distance = axis.length();
+
+    if distance >= radius {
+        return None;
+    }
+
+    Some(Contact::new(-axis / distance, radius - distance, closest))
+}
+
+/// SAT test between two convex polygons given as world space vertices in
+/// counter-clockwise order.
+///
+/// Produces up to two contact points by clipping the incident edge against
+/// the reference edge.
+pub fn contact_polygons(a: &[Vec2], b: &[Vec2]) -> Option<Contact> {
+    contact_rounded_polygons(a, 0.0, b, 0.0)
+}
+
+/// Same as `contact_polygons`, but each polygon is inflated by its radius.
+///
+/// A two vertex polygon is a segment, which makes this also handle
+/// capsules against polygons.
+fn contact_rounded_polygons(
+    a: &[Vec2],
+    radius_a: f32,
+    b: &[Vec2],
+    radius_b: f32,
+) -> Option<Contact> {
+    let total_radius = radius_a + radius_b;
+
+    let (edge_a, separation_a) = max_separation(a, b);
+    if separation_a > total_radius {
+        return None;
+    }
+
+    let (edge_b, separation_b) = max_separation(b, a);
+    if separation_b > total_radius {
+        return None;
+    }
+
+    // Prefer `a` as the reference polygon to avoid flip-flopping between
+    // nearly equal axes from one step to the next.
+    let flip = separation_b > 0.98 * separation_a + 0.001;
+
+    let (reference, incident, reference_edge, incident_radius) = if flip {
+        (b, a, edge_b, radius_a)
+    } else {
+        (a, b, edge_a, radius_b)
+    };
+
+    let r1 = reference[reference_edge];
+    let r2 = reference[(reference_edge + 1) % reference.len()];
+    let reference_normal = edge_normal(r1, r2);
+
+    let incident_edge = (0..incident.len())
+        .min_by(|&i, &j| {
+            let dot_i =
+                edge_normal(incident[i], incident[(i + 1) % incident.len()]).dot(reference_normal);
+            let dot_j =
+                edge_normal(incident[j], incident[(j + 1) % incident.len()]).dot(reference_normal);
+
+            dot_i.total_cmp(&dot_j)
+        })
+        .unwrap();
+
+    let segment = [
+        incident[incident_edge],
+        incident[(incident_edge + 1) % incident.len()],
+    ];
+
+    let tangent = (r2 - r1).normalize();
+    let segment = clip_segment(segment, -tangent, -tangent.dot(r1))?;
+    let segment = clip_segment(segment, tangent, tangent.dot(r2))?;
+
+    let mut contact = Contact {
+        // The reference normal points away from the reference polygon.
+        normal: if flip {
+            reference_normal
+        } else {
+            -reference_normal
+        },
+        depth: 0.0,
+        points: [Vec2::ZERO; 2],
+        point_count: 0,
+    };
+
+    for point in segment {
+        let separation = reference_normal.dot(point - r1);
+
+        if separation <= total_radius {
+            contact.points[contact.point_count] = point - reference_normal * incident_radius;
+            contact.point_count += 1;
+            contact.depth = contact.depth.max(total_radius - separation);
+        }
+    }
+
+    (contact.point_count > 0).then_some(contact)
+}
+
+/// Contact between a capsule (first shape) and a ball (second shape).
+pub fn contact_capsule_ball(
+    capsule: &Capsule,
+    transform: Affine2,
+    center: Vec2,
+    radius: f32,
+) -> Option<Contact> {
+    let [a, b] = capsule.world_segment(transform);
+    let closest = closest_point_on_segment(a, b, center);
+
+    contact_ball_ball(
+        closest,
+        capsule.radius * transform.scale_factor(),
+        center,
+        radius,
+    )
+}
+
+pub fn contact_capsule_capsule(
+    capsule_a: &Capsule,
+    transform_a: Affine2,
+    capsule_b: &Capsule,
+    transform_b: Affine2,
+) -> Option<Contact> {
+    let [a1, a2] = capsule_a.world_segment(transform_a);
+    let [b1, b2] = capsule_b.world_segment(transform_b);
+    let (closest_a, closest_b) = closest_points_on_segments(a1, a2, b1, b2);
+
+    contact_ball_ball(
+        closest_a,
+        capsule_a.radius * transform_a.scale_factor(),
+        closest_b,
+        capsule_b.radius * transform_b.scale_factor(),
+    )
+}
+
+/// Contact between a convex polygon (first shape) given as world space
+/// vertices in counter-clockwise order and a capsule (second shape).
+pub fn contact_polygon_capsule(
+    vertices: &[Vec2],
+    capsule: &Capsule,
+    transform: Affine2,
+) -> Option<Contact> {
+    let segment = capsule.world_segment(transform);
+    let radius = capsule.radius * transform.scale_factor();
+
+    if segment[0].distance_squared(segment[1]) < 1e-12 {
+        return contact_polygon_ball(vertices, segment[0], radius);
+    }
+
+    contact_rounded_polygons(vertices, 0.0, &segment, radius)
+}
+
+/// Contact between a convex polygon (first shape) given as world space
+/// vertices in counter-clockwise order and a cuboid, convex polygon or
+/// capsule (second shape).
+fn contact_polygon_convex(
+    vertices: &[Vec2],
+    shape: &dyn Shape,
+    transform: Affine2,
+) -> Option<Contact> {
+    if let Some(capsule) = shape.as_capsule() {
+        return contact_polygon_capsule(vertices, capsule, transform);
+    }
+
+    contact_polygons(vertices, &world_polygon(shape, transform)?)
+}
+
+/// World space vertices of shapes that can be treated as convex polygons.
+fn world_polygon(shape: &dyn Shape, transform: Affine2) -> Option<Vec<Vec2>> {
+    if let Some(cuboid) = shape.as_cuboid() {
+        return Some(cuboid.world_vertices(transform).to_vec());
+    }
+
+    shape
+        .as_convex_polygon()
+        .map(|polygon| polygon.world_vertices(transform))
+}
+
+pub fn closest_point_on_segment(a: Vec2, b: Vec2, point: Vec2) -> Vec2 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+
+    if length_squared < 1e-12 {
+        return a;
+    }
+
+    let t = ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+/// Closest points between segments `a1 a2` and `b1 b2`.
+pub fn closest_points_on_segments(a1: Vec2, a2: Vec2, b1: Vec2, b2: Vec2) -> (Vec2, Vec2) {
+    let d1 = a2 - a1;
+    let d2 = b2 - b1;
+    let r = a1 - b1;
+
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+
+    if a < 1e-12 && e < 1e-12 {
+        return (a1, b1);
+    }
+
+    let (s, t) = if a < 1e-12 {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(r);
+
+        if e < 1e-12 {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(d2);
+            let denom = a * e - b * b;
+
+            // Parallel segments pick an arbitrary point on the first one.
+            let s = if denom > 1e-12 {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+
+            let t = (b * s + f) / e;
+
+            if t < 0.0 {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else if t > 1.0 {
+                (((b - c) / a).clamp(0.0, 1.0), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+
+    (a1 + d1 * s, b1 + d2 * t)
+}
+
+/// Outward normal of a counter-clockwise polygon edge.
+fn edge_normal(v1: Vec2, v2: Vec2) -> Vec2 {
+    let edge = v2 - v1;
+    vec2(edge.y, -edge.x).normalize_or_zero()
+}
+
+/// Finds the edge of `a` along whose normal `b` is separated the most.
+fn max_separation(a: &[Vec2], b: &[Vec2]) -> (usize, f32) {
+    let mut best_edge = 0;
+    let mut best_separation = f32::MIN;
+
+    for i in 0..a.len() {
+        let v1 = a[i];
+        let normal = edge_normal(v1, a[(i + 1) % a.len()]);
+
+        let separation = b
+            .iter()
+            .map(|point| normal.dot(*point - v1))
+            .fold(f32::MAX, f32::min);
+
+        if separation > best_separation {
+            best_separation = separation;
+            best_edge = i;
+        }
+    }
+
+    (best_edge, best_separation)
+}
+
+/// Keeps the part of the segment for which `normal.dot(p) <= offset`.
+fn clip_segment(segment: [Vec2; 2], normal: Vec2, offset: f32) -> Option<[Vec2; 2]> {
+    let [p0, p1] = segment;
+
+    let d0 = normal.dot(p0) - offset;
+    let d1 = normal.dot(p1) - offset;
+
+    match (d0 <= 0.0, d1 <= 0.0) {
+        (true, true) => Some(segment),
+        (false, false) => None,
+        _ => {
+            let intersection = p0 + (p1 - p0) * (d0 / (d0 - d1));
+
+            if d0 <= 0.0 {
+                Some([p0, intersection])
+            } else {
+                Some([intersection, p1])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::f32::consts::{FRAC_PI_4, PI};
+
+    #[test]
+    fn ball_ball_contact() {
+        let contact = contact_ball_ball(vec2(0.0, 0.0), 1.0, vec2(1.5, 0.0), 1.0).unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(-1.0, 0.0));
+        assert_relative_eq!(contact.depth, 0.5);
+
+        assert!(contact_ball_ball(vec2(0.0, 0.0), 1.0, vec2(2.5, 0.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn rotated_cuboid_aabb() {
+        let cuboid = Cuboid::new(vec2(1.0, 1.0));
+        let aabb = cuboid.calculate_aabb(Affine2::from_angle_translation(PI / 4.0, vec2(1.0, 0.0)));
+
+        let extent = 2.0f32.sqrt();
+        assert_relative_eq!(aabb.min, vec2(1.0 - extent, -extent), epsilon = 1e-5);
+        assert_relative_eq!(aabb.max, vec2(1.0 + extent, extent), epsilon = 1e-5);
+    }
+
+    #[test]
+    fn ball_resting_on_cuboid() {
+        let cuboid = Cuboid::new(vec2(2.0, 1.0));
+        let ball = Ball::new(0.5);
+
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(0.5, 1.4)),
+            &cuboid,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+        assert_relative_eq!(contact.points()[0], vec2(0.5, 1.0), epsilon = 1e-5);
+
+        // Center inside the box gets pushed out through the closest face.
+        let contact = contact_cuboid_ball(&cuboid, Affine2::IDENTITY, vec2(1.8, 0.0), 0.5).unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(-1.0, 0.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.7, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn ball_against_sheared_cuboid() {
+        // A collider rotated by its offset on a body scaled by (2, 1) turns
+        // into a rhombus with corners at (±2√2, 0) and (0, ±√2).
+        let transform = Affine2::from_scale(vec2(2.0, 1.0)) * Affine2::from_angle(FRAC_PI_4);
+        let cuboid = Cuboid::new(vec2(1.0, 1.0));
+
+        assert!(!transform.has_uniform_scale());
+
+        let contact = contact_shapes(
+            &Ball::new(1.0),
+            Affine2::from_translation(vec2(1.5, 1.5)),
+            &cuboid,
+            transform,
+        )
+        .unwrap();
+
+        let face_normal = vec2(1.0, 2.0).normalize();
+        let distance =
+            (face_normal.dot(vec2(1.5, 1.5)) - face_normal.dot(vec2(0.0, 2.0f32.sqrt()))).abs();
+
+        assert_relative_eq!(contact.normal, face_normal, epsilon = 1e-4);
+        assert_relative_eq!(contact.depth, 1.0 - distance, epsilon = 1e-4);
+        assert_relative_eq!(
+            contact.points()[0],
+            vec2(1.5, 1.5) - face_normal * distance,
+            epsilon = 1e-4
+        );
+    }
+
+    #[test]
+    fn stacked_cuboids() {
+        let cuboid = Cuboid::new(vec2(1.0, 1.0));
+
+        let contact = contact_shapes(
+            &cuboid,
+            Affine2::from_translation(vec2(0.5, 1.9)),
+            &cuboid,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+        assert_eq!(contact.point_count, 2);
+
+        for point in contact.points() {
+            assert!(point.x >= -0.5 - 1e-5 && point.x <= 1.0 + 1e-5);
+        }
+
+        assert!(contact_shapes(
+            &cuboid,
+            Affine2::from_angle_translation(PI / 4.0, vec2(0.0, 2.5)),
+            &cuboid,
+            Affine2::IDENTITY,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn polygon_contacts() {
+        // Clockwise input gets reordered.
+        let triangle = ConvexPolygon::new(vec![vec2(-1.0, 0.0), vec2(0.0, 1.0), vec2(1.0, 0.0)]);
+        assert_eq!(triangle.points[0], vec2(1.0, 0.0));
+
+        let ball = Ball::new(0.5);
+
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(0.0, -0.4)),
+            &triangle,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, -1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+
+        // Ball near a vertex is pushed along the vertex direction.
+        let contact = contact_shapes(
+            &triangle,
+            Affine2::IDENTITY,
+            &ball,
+            Affine2::from_translation(vec2(1.3, -0.3)),
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(-1.0, 1.0).normalize(), epsilon = 1e-5);
+        assert_relative_eq!(contact.points()[0], vec2(1.0, 0.0), epsilon = 1e-5);
+
+        let cuboid = Cuboid::new(vec2(1.0, 0.5));
+
+        let contact = contact_shapes(
+            &triangle,
+            Affine2::from_translation(vec2(0.0, 0.4)),
+            &cuboid,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+        assert_eq!(contact.point_count, 2);
+    }
+
+    #[test]
+    fn capsule_contacts() {
+        let capsule = Capsule::new(1.0, 0.5);
+        let ball = Ball::new(0.5);
+
+        // Ball touching the side of the segment.
+        let contact = contact_shapes(
+            &capsule,
+            Affine2::IDENTITY,
+            &ball,
+            Affine2::from_translation(vec2(0.9, 0.5)),
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(-1.0, 0.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+
+        // Capsule lying on its side resting on another one standing up.
+        let contact = contact_shapes(
+            &capsule,
+            Affine2::from_angle_translation(PI / 2.0, vec2(0.0, 1.9)),
+            &capsule,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+
+        // Capsule lying on its side on top of a box touches along its segment.
+        let cuboid = Cuboid::new(vec2(2.0, 0.5));
+        let contact = contact_shapes(
+            &capsule,
+            Affine2::from_angle_translation(PI / 2.0, vec2(0.0, 0.9)),
+            &cuboid,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+        assert_eq!(contact.point_count, 2);
+    }
+
+    #[test]
+    fn capsule_mass_properties() {
+        let disc = Capsule::new(0.0, 1.0);
+
+        assert_relative_eq!(disc.area(), PI);
+        assert_relative_eq!(disc.inertia(2.0), 1.0);
+
+        let collider = ColliderBuilder::new()
+            .shape(Box::new(Capsule::new(1.0, 0.5)))
+            .build();
+
+        assert_relative_eq!(collider.mass(), 2.0 + PI * 0.25);
+        assert!(collider.inertia() > collider.mass() * 0.25);
+    }
+
+    /// A custom box claiming to be a ball.
+    #[derive(Debug)]
+    struct Impostor;
+
+    impl Shape for Impostor {
+        fn shape_type(&self) -> ShapeType {
+            ShapeType::Ball
+        }
+
+        fn calculate_aabb(&self, transform: Affine2) -> AABB {
+            Cuboid::new(Vec2::ONE).calculate_aabb(transform)
+        }
+
+        fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2> {
+            Cuboid::new(Vec2::ONE).support_point(transform, direction)
+        }
+    }
+
+    #[test]
+    fn custom_shapes_fall_back_to_gjk() {
+        let contact = contact_shapes(
+            &Impostor,
+            Affine2::from_translation(vec2(0.0, 1.4)),
+            &Ball::new(0.5),
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-4);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-4);
+
+        let contact = contact_shapes(
+            &Cuboid::new(Vec2::ONE),
+            Affine2::IDENTITY,
+            &Impostor,
+            Affine2::from_translation(vec2(1.9, 0.0)),
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(-1.0, 0.0), epsilon = 1e-4);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn shape_mass_properties() {
+        let ball = Ball::new(2.0).mass_properties(0.5);
+        assert_relative_eq!(ball.mass, 2.0 * PI);
+        assert_relative_eq!(ball.inertia, 4.0 * PI);
+
+        let cuboid = Cuboid::new(vec2(1.0, 0.5)).mass_properties(2.0);
+        assert_relative_eq!(cuboid.mass, 4.0);
+        assert_relative_eq!(cuboid.inertia, 4.0 * 5.0 / 12.0);
+
+        // The same box as a polygon shifted away from the origin.
+        let polygon = ConvexPolygon::new(vec![
+            vec2(2.0, 0.0),
+            vec2(4.0, 0.0),
+            vec2(4.0, 1.0),
+            vec2(2.0, 1.0),
+        ])
+        .mass_properties(2.0);
+
+        assert_relative_eq!(polygon.mass, 4.0);
+        assert_relative_eq!(polygon.local_center_of_mass, vec2(3.0, 0.5));
+        assert_relative_eq!(polygon.inertia, cuboid.inertia, epsilon = 1e-4);
+
+        let polyline = Polyline::new(vec![vec2(0.0, 0.0), vec2(1.0, 0.0)]);
+        assert_eq!(polyline.mass_properties(1.0).mass, 0.0);
+
+        let collider = ColliderBuilder::ball(1.0)
+            .density(2.0)
+            .mass_override(1.0)
+            .build();
+        assert_relative_eq!(collider.mass(), 1.0);
+        assert_relative_eq!(collider.inertia(), 0.5);
+    }
+
+    #[test]
+    fn polyline_contacts() {
+        let ground = Polyline::new(vec![vec2(-2.0, 0.0), vec2(0.0, 0.0), vec2(2.0, 1.0)]);
+        let ball = Ball::new(0.5);
+
+        // Directly above the shared vertex of a concave join, the closest
+        // feature is the vertex itself.
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(0.0, 0.4)),
+            &ground,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert!(contact.normal.y > 0.0);
+        assert!(contact.depth > 0.0);
+
+        // Sliding along the flat part near the join keeps a vertical normal.
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(-0.3, 0.4)),
+            &ground,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+
+        // Two-sided chains push balls out below, one-sided ones let them through.
+        let below = Affine2::from_translation(vec2(-1.0, -0.4));
+        let contact = contact_shapes(&ball, below, &ground, Affine2::IDENTITY).unwrap();
+        assert_relative_eq!(contact.normal, vec2(0.0, -1.0), epsilon = 1e-5);
+
+        let one_sided = ground.clone().one_sided(true);
+        assert!(contact_shapes(&ball, below, &one_sided, Affine2::IDENTITY).is_none());
+
+        // Boxes and capsules collide with each segment they overlap.
+        let cuboid = Cuboid::new(vec2(0.5, 0.5));
+        let resting = Affine2::from_translation(vec2(-1.0, 0.4));
+        let contact = contact_shapes(&cuboid, resting, &ground, Affine2::IDENTITY).unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+        assert_eq!(contact.point_count, 2);
+
+        let capsule = Capsule::new(0.5, 0.25);
+        let contact = contact_shapes(&capsule, resting, &ground, Affine2::IDENTITY).unwrap();
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+
+        let below = Affine2::from_translation(vec2(-1.0, -0.4));
+        assert!(contact_shapes(&cuboid, below, &one_sided, Affine2::IDENTITY).is_none());
+
+        // The margin is only honoured against balls, but it doesn't drop the pair.
+        let contact =
+            contact_shapes_with_margin(&cuboid, resting, &ground, Affine2::IDENTITY, 0.1).unwrap();
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn heightfield_contacts() {
+        let hills = Heightfield::new(vec![0.0, 1.0, 0.0, 0.0, 0.0], vec2(8.0, 1.0));
+        let ball = Ball::new(0.5);
+
+        assert_eq!(hills.cell_width(), 2.0);
+        assert_eq!(hills.local_point(1), vec2(-2.0, 1.0));
+        assert_eq!(hills.cells_in_range(0.5, 1.5), 2..3);
+        assert_eq!(hills.cells_in_range(-10.0, -5.0), 0..0);
+
+        // Resting on a flat cell.
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(1.0, 0.4)),
+            &hills,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+
+        // Deep below the surface still gets pushed up.
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(1.0, -3.0)),
+            &hills,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 3.5, epsilon = 1e-5);
+
+        // Near the peak the closest feature is the sample itself.
+        let contact = contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(-2.0, 1.4)),
+            &hills,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+
+        assert!(contact_shapes(
+            &ball,
+            Affine2::from_translation(vec2(1.0, 1.0)),
+            &hills,
+            Affine2::IDENTITY,
+        )
+        .is_none());
+
+        // Boxes rest on the surface and get pushed up from deep below it.
+        let cuboid = Cuboid::new(vec2(0.5, 0.5));
+        let contact = contact_shapes(
+            &cuboid,
+            Affine2::from_translation(vec2(2.0, 0.4)),
+            &hills,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 0.1, epsilon = 1e-5);
+
+        let contact = contact_shapes(
+            &cuboid,
+            Affine2::from_translation(vec2(2.0, -3.0)),
+            &hills,
+            Affine2::IDENTITY,
+        )
+        .unwrap();
+
+        assert_relative_eq!(contact.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+        assert_relative_eq!(contact.depth, 3.5, epsilon = 1e-5);
+
+        assert!(contact_shapes(
+            &Capsule::new(0.5, 0.25),
+            Affine2::from_translation(vec2(2.0, 1.0)),
+            &hills,
+            Affine2::IDENTITY,
+        )
+        .is_none());
+    }
+}
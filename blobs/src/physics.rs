@@ -1,7 +1,9 @@
 use crate::*;
 
 pub struct Physics {
-    pub time_data: Rc<TimeData>,
+    pub time_data: Rc<Cell<TimeData>>,
+    /// Diagnostics of this world, shared with `rbd_set` and `col_set`.
+    pub event_history: Rc<RefCell<EventHistory>>,
 
     pub gravity: Vec2,
 
@@ -16,11 +18,20 @@ pub struct Physics {
 
     pub query_pipeline: QueryPipeline,
 
-    pub spatial_hash: SpatialHash,
-    pub use_spatial_hash: bool,
+    pub broad_phase: Box<dyn BroadPhase>,
+    pub hooks: Box<dyn PhysicsHooks>,
 
-    pub collision_send: Sender<CollisionEvent>,
-    pub collision_recv: Receiver<CollisionEvent>,
+    /// Removals delivered to the `EventHandler` at the start of the next step.
+    pub pending_removals: Vec<RemovalEvent>,
+    /// Contacts and sensor overlaps ended by removals, delivered at the start
+    /// of the next step before the removals themselves.
+    pub pending_collision_events: Vec<CollisionEvent>,
+    pub pending_sensor_events: Vec<SensorEvent>,
+
+    /// Colliders touching as of the last step.
+    pub contact_pairs: ContactPairSet,
+    /// Sensors and the colliders overlapping them as of the last step.
+    pub sensor_pairs: ContactPairSet,
 
     // Quick way to disable all collision calculations,
     // useful for testing/measurements.
@@ -34,55 +45,89 @@ pub struct Physics {
 }
 
 impl Physics {
-    pub fn new(gravity: Vec2, use_spatial_hash: bool) -> Self {
-        let (send, recv) = std::sync::mpsc::channel();
-
-        let time_data = Rc::new(TimeData::new());
+    /// Creates a world using `BruteForceBroadPhase`, see `with_broad_phase`.
+    pub fn new(gravity: Vec2) -> Self {
+        let time_data = Rc::new(Cell::new(TimeData::new()));
+        let event_history = Rc::new(RefCell::new(EventHistory::new()));
 
         Self {
             gravity,
             time_data: time_data.clone(),
+            event_history: event_history.clone(),
 
             substeps: 8,
             joint_iterations: 4,
 
-            rbd_set: RigidBodySet::new(time_data.clone()),
-            col_set: ColliderSet::new(time_data.clone()),
+            rbd_set: RigidBodySet::new(time_data.clone(), event_history.clone()),
+            col_set: ColliderSet::new(time_data.clone(), event_history),
             joints: Arena::new(),
             springs: Arena::new(),
 
             query_pipeline: QueryPipeline::new(time_data),
 
-            use_spatial_hash,
+            broad_phase: Box::new(BruteForceBroadPhase),
+            hooks: Box::new(()),
             constraints: vec![],
 
-            collision_send: send,
-            collision_recv: recv,
+            pending_removals: vec![],
+            pending_collision_events: vec![],
+            pending_sensor_events: vec![],
+            contact_pairs: ContactPairSet::new(),
+            sensor_pairs: ContactPairSet::new(),
 
             collisions_enabled: true,
 
             accumulator: 0.0,
             time: 0.0,
-            spatial_hash: SpatialHash::new(2.0),
             old_dt: 1.0,
         }
     }
 
+    pub fn with_broad_phase(mut self, broad_phase: impl BroadPhase + 'static) -> Self {
+        self.broad_phase = Box::new(broad_phase);
+        self
+    }
+
+    /// Swaps the broad phase, the new one starts from the current colliders.
+    pub fn set_broad_phase(&mut self, broad_phase: Box<dyn BroadPhase>) {
+        self.broad_phase = broad_phase;
+    }
+
+    pub fn with_hooks(mut self, hooks: impl PhysicsHooks + 'static) -> Self {
+        self.hooks = Box::new(hooks);
+        self
+    }
+
+    pub fn set_hooks(&mut self, hooks: Box<dyn PhysicsHooks>) {
+        self.hooks = hooks;
+    }
+
     pub fn reset(&mut self) {
         self.rbd_set.arena.clear();
         self.col_set.arena.clear();
         self.joints.clear();
         self.springs.clear();
+        self.broad_phase.clear();
+        self.query_pipeline.clear();
+        self.contact_pairs.clear();
+        self.sensor_pairs.clear();
+        self.pending_removals.clear();
+        self.pending_collision_events.clear();
+        self.pending_sensor_events.clear();
     }
 
-    pub fn step(&mut self, delta: f64) {
+    pub fn step(&mut self, delta: f64, events: &mut dyn EventHandler) {
         let _span = tracy_span!("step");
-        self.integrate(self.substeps, delta as f32);
+        self.flush_pending_events(events);
+        self.integrate(self.substeps, delta as f32, events);
         self.time += delta;
+        self.update_time_data(delta);
+        self.update_query_pipeline();
     }
 
-    pub fn fixed_step(&mut self, frame_time: f64) {
+    pub fn fixed_step(&mut self, frame_time: f64, events: &mut dyn EventHandler) {
         let _span = tracy_span!("step");
+        self.flush_pending_events(events);
         self.accumulator += frame_time;
 
         let delta = 1.0 / 60.0;
@@ -90,12 +135,47 @@ impl Physics {
 
         while self.accumulator >= delta && max_steps > 0 {
             let _span = tracy_span!("integrate");
-            self.integrate(self.substeps, delta as f32);
+            self.integrate(self.substeps, delta as f32, events);
 
             self.accumulator -= delta;
             self.time += delta;
             max_steps -= 1;
         }
+
+        self.update_time_data(frame_time);
+        self.update_query_pipeline();
+    }
+
+    fn update_time_data(&self, frame_time: f64) {
+        let mut time_data = self.time_data.get();
+        time_data.real_time += frame_time;
+        time_data.unpaused_time = self.time;
+        self.time_data.set(time_data);
+    }
+
+    /// Syncs the query pipeline with colliders moved outside of a step, like
+    /// bodies teleported by setting their position.
+    pub fn update_query_pipeline(&mut self) {
+        self.query_pipeline.update(&self.col_set);
+    }
+
+    /// Reads this world's diagnostics, see `EventHistory` for the queries.
+    pub fn event_history(&self) -> std::cell::Ref<'_, EventHistory> {
+        self.event_history.borrow()
+    }
+
+    fn flush_pending_events(&mut self, events: &mut dyn EventHandler) {
+        for event in self.pending_collision_events.drain(..) {
+            events.handle_collision_event(event);
+        }
+
+        for event in self.pending_sensor_events.drain(..) {
+            events.handle_sensor_event(event);
+        }
+
+        for event in self.pending_removals.drain(..) {
+            events.handle_removal_event(event);
+        }
     }
 
     pub fn get_rbd_data(&self, handle: RigidBodyHandle) -> Option<RigidBodyData> {
@@ -119,12 +199,7 @@ impl Physics {
     }
 
     pub fn insert_rbd(&mut self, rbd: RigidBody) -> RigidBodyHandle {
-        let position = rbd.position;
-
-        let handle = self.rbd_set.insert(rbd);
-        self.spatial_hash
-            .insert_with_id(handle.0.to_bits(), position, 0.5);
-        handle
+        self.rbd_set.insert(rbd)
     }
 
     pub fn insert_collider_with_parent(
@@ -132,22 +207,59 @@ impl Physics {
         collider: Collider,
         rbd_handle: RigidBodyHandle,
     ) -> ColliderHandle {
+        let is_static = self.rbd_set.get(rbd_handle).is_some_and(|rbd| rbd.is_static());
+
+        if collider.shape.as_polyline().is_some() && !is_static {
+            self.event_history.borrow_mut().push(PhysicsEvent {
+                time_data: self.time_data.get(),
+                position: self.rbd_position(rbd_handle),
+                message: "polyline collider attached to a non-static rigid body".into(),
+                severity: Severity::Warn,
+                col_handle: None,
+                rbd_handle: Some(rbd_handle),
+            });
+        }
+
         let col_handle = self
             .col_set
             .insert_with_parent(collider, rbd_handle, &mut self.rbd_set);
 
+        // `insert_with_parent` already registered the collider on the body.
         let rbd = self
             .rbd_set
             .get_mut(rbd_handle)
             .expect("parent rigid body must exist when inserting collider");
 
-        rbd.colliders.push(col_handle);
-
         rbd.update_mass_and_inertia(&self.col_set);
 
+        if let Some(collider) = self.col_set.get(col_handle) {
+            self.query_pipeline.update_collider(col_handle, collider);
+        }
+
         col_handle
     }
 
+    /// Latest data of a pair touching during the last step, in either order.
+    pub fn contact_pair(&self, a: ColliderHandle, b: ColliderHandle) -> Option<&CollisionEvent> {
+        self.contact_pairs.get(a, b)
+    }
+
+    /// Colliders overlapping `sensor` as of the last step.
+    pub fn sensor_overlaps(&self, sensor: ColliderHandle) -> Vec<ColliderHandle> {
+        self.sensor_pairs
+            .iter()
+            .filter_map(|event| {
+                if event.col_handle_a == sensor {
+                    Some(event.col_handle_b)
+                } else if event.col_handle_b == sensor {
+                    Some(event.col_handle_a)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
     pub fn rbd_position(&self, handle: RigidBodyHandle) -> Option<Vec2> {
         self.rbd_set.get(handle).map(|x| x.position)
     }
@@ -157,18 +269,78 @@ impl Physics {
     }
 
     pub fn remove_col(&mut self, handle: ColliderHandle) {
+        let parent = self.col_set.get(handle).and_then(|x| x.parent);
+
+        if self.col_set.get(handle).is_some() {
+            self.stop_pairs_with(handle);
+            self.pending_removals.push(RemovalEvent::Collider(handle));
+        }
+
         self.col_set.remove(handle, &mut self.rbd_set);
+        self.broad_phase.remove(handle);
+        self.query_pipeline.remove_collider(handle);
+
+        // The parent is removed along with its last collider.
+        if let Some(parent) = parent {
+            if self.rbd_set.get(parent).is_none() {
+                self.pending_removals.push(RemovalEvent::RigidBody(parent));
+            }
+        }
     }
 
     pub fn remove_rbd(&mut self, handle: RigidBodyHandle) {
         if let Some(rbd) = self.rbd_set.get(handle) {
-            for col_handle in rbd.colliders() {
-                self.col_set.remove_ignoring_parent(*col_handle);
+            let colliders = rbd.colliders().copied().collect::<Vec<_>>();
+
+            for col_handle in colliders {
+                self.stop_pairs_with(col_handle);
+                self.col_set.remove_ignoring_parent(col_handle);
+                self.broad_phase.remove(col_handle);
+                self.query_pipeline.remove_collider(col_handle);
+                self.pending_removals.push(RemovalEvent::Collider(col_handle));
             }
+
+            self.pending_removals.push(RemovalEvent::RigidBody(handle));
         }
 
         self.rbd_set.remove_rbd(handle);
-        self.spatial_hash.remove(handle.0.to_bits());
+    }
+
+    /// Drops the pairs of a collider about to be removed, queueing their
+    /// `Stopped`/`Exit` events. Must run while the collider still exists.
+    fn stop_pairs_with(&mut self, handle: ColliderHandle) {
+        for event in self.contact_pairs.remove_collider(handle) {
+            if self.pair_events(&event).contains(ActiveEvents::COLLISION_EVENTS) {
+                self.pending_collision_events.push(event);
+            }
+        }
+
+        for event in self.sensor_pairs.remove_collider(handle) {
+            if self.pair_events(&event).contains(ActiveEvents::SENSOR_EVENTS) {
+                self.pending_sensor_events.push(Self::sensor_event(
+                    SensorEventKind::Exit,
+                    &event,
+                    &self.col_set,
+                ));
+            }
+        }
+    }
+
+    /// Rescales a body along its local axes, updating its collider
+    /// transforms and recomputing its mass and inertia.
+    pub fn set_rbd_scale(&mut self, handle: RigidBodyHandle, scale: Vec2) {
+        let Some(rbd) = self.rbd_set.get_mut(handle) else { return; };
+
+        rbd.scale = scale;
+
+        for col_handle in rbd.colliders() {
+            if let Some(collider) = self.col_set.get_mut(*col_handle) {
+                collider.absolute_transform = rbd.transform() * collider.offset;
+                self.query_pipeline.update_collider(*col_handle, collider);
+            }
+        }
+
+        rbd.update_mass_and_inertia(&self.col_set);
     }
 
     pub fn update_rigid_body_position(&mut self, id: u64, offset: Vec2) {
@@ -176,7 +348,6 @@ impl Physics {
             .rbd_set
             .get_mut(RigidBodyHandle(Index::from_bits(id).unwrap()))
         {
-            self.spatial_hash.move_point(id, offset);
             rigid_body.position += offset;
         }
     }
@@ -238,92 +409,183 @@ impl Physics {
         joint_handle
     }
 
-    pub fn brute_force_collisions(&mut self) {
-        let _span = tracy_span!("brute_force_collisions");
+    /// Runs the broad phase and resolves every touching candidate pair.
+    pub fn collisions(&mut self, events: &mut dyn EventHandler) {
+        let _span = tracy_span!("collisions");
 
-        let keys = self.col_set.iter().map(|(idx, _)| idx).collect_vec();
+        let pairs = self.broad_phase.find_pairs(&self.col_set);
 
         let mut count = 0;
 
-        for (i, idx_a) in keys.iter().enumerate() {
-            for idx_b in keys.iter().take(i) {
-                let (Some(col_a), Some(col_b)) = self.col_set.get2_mut(*idx_a, *idx_b) else { continue; };
+        for (idx_a, idx_b) in pairs {
+            if self.solve_contact_pair(idx_a, idx_b, events) {
+                count += 1;
+            }
+        }
 
-                let Some(parent_a) = col_a.parent else { continue; };
-                let Some(parent_b) = col_b.parent else { continue; };
+        perf_counter_inc("collisions", count);
+    }
 
-                if !col_a.collision_groups.test(col_b.collision_groups) {
-                    continue;
-                }
+    /// Narrow phase and positional correction for a single candidate pair.
+    /// Returns `true` if the colliders touch.
+    fn solve_contact_pair(
+        &mut self,
+        idx_a: ColliderHandle,
+        idx_b: ColliderHandle,
+        events: &mut dyn EventHandler,
+    ) -> bool {
+        let (Some(col_a), Some(col_b)) = (self.col_set.get(idx_a), self.col_set.get(idx_b)) else { return false; };
 
-                // Colliders share a rigid body
-                if parent_a == parent_b {
-                    continue;
-                }
+        let Some(parent_a) = col_a.parent else { return false; };
+        let Some(parent_b) = col_b.parent else { return false; };
 
-                let mut axis = col_a.absolute_translation() - col_b.absolute_translation();
-                let mut distance = axis.length();
+        if !col_a.collision_groups.test(col_b.collision_groups) {
+            return false;
+        }
 
-                let min_dist = col_a.radius + col_b.radius;
+        // Colliders share a rigid body
+        if parent_a == parent_b {
+            return false;
+        }
 
-                if distance < min_dist {
-                    let (Some(rbd_a), Some(rbd_b)) = self.rbd_set.arena.get2_mut(parent_a.0, parent_b.0) else { continue; };
+        let (Some(rbd_a), Some(rbd_b)) = (self.rbd_set.get(parent_a), self.rbd_set.get(parent_b)) else { return false; };
 
-                    if distance < 1e-6 {
-                        // A small push-out value to separate the objects
-                        let push_out = Vec2::new(0.01, 0.0);
-                        rbd_a.position += push_out;
-                        rbd_b.position -= push_out;
+        if rbd_a.is_static() && rbd_b.is_static() {
+            return false;
+        }
 
-                        col_a.absolute_transform.translation =
-                            rbd_a.position + col_a.offset.translation;
-                        col_b.absolute_transform.translation =
-                            rbd_b.position + col_b.offset.translation;
+        let pair = PairContext {
+            rbd_set: &self.rbd_set,
+            col_set: &self.col_set,
+            collider_a: idx_a,
+            collider_b: idx_b,
+            rigid_body_a: parent_a,
+            rigid_body_b: parent_b,
+            time: self.time,
+        };
 
-                        // Recalculate axis and distance
-                        axis = col_a.absolute_translation() - col_b.absolute_translation();
-                        distance = axis.length();
-                    }
+        if !self.hooks.filter_contact_pair(&pair) {
+            return false;
+        }
 
-                    let impact_vel_a = rbd_a.calculated_velocity;
-                    let impact_vel_b = rbd_b.calculated_velocity;
+        let Some(mut contact) = Self::collider_contact(col_a, col_b) else { return false; };
 
-                    if !col_a.flags.is_sensor && !col_b.flags.is_sensor {
-                        let n = axis / distance;
-                        assert!(!n.is_nan());
-                        let delta = min_dist - distance;
+        let mut modification = ContactModification::new(&contact);
+        self.hooks.modify_contact(&pair, &mut modification);
 
-                        let ratio = Self::mass_ratio(rbd_a, rbd_b);
+        contact.normal = modification.normal;
+        contact.depth = modification.depth;
 
-                        rbd_a.position += ratio * delta * n;
-                        rbd_b.position -= (1.0 - ratio) * delta * n;
-                    }
+        let is_sensor = col_a.flags.is_sensor || col_b.flags.is_sensor;
+        let active_events = col_a.active_events | col_b.active_events;
 
-                    count += 1;
+        let (Some(rbd_a), Some(rbd_b)) = self.rbd_set.arena.get2_mut(parent_a.0, parent_b.0) else { return false; };
 
-                    self.collision_send
-                        .send(CollisionEvent {
-                            col_handle_a: *idx_a,
-                            col_handle_b: *idx_b,
-                            impact_vel_a,
-                            impact_vel_b,
-                        })
-                        .unwrap();
-                }
+        let impact_vel_a = rbd_a.calculated_velocity;
+        let impact_vel_b = rbd_b.calculated_velocity;
+
+        let mut manifold = ContactManifold::new(contact, impact_vel_a - impact_vel_b);
+
+        if !is_sensor && !modification.pass_through {
+            let n = contact.normal;
+            assert!(!n.is_nan());
+            let delta = contact.depth * modification.correction_scale;
+
+            let ratio = Self::mass_ratio(rbd_a, rbd_b);
+
+            manifold.correction_a = ratio * delta * n;
+            manifold.correction_b = -(1.0 - ratio) * delta * n;
+
+            rbd_a.position += manifold.correction_a;
+            rbd_b.position += manifold.correction_b;
+        }
+
+        let event = CollisionEvent {
+            kind: CollisionEventKind::Started,
+            col_handle_a: idx_a,
+            col_handle_b: idx_b,
+            impact_vel_a,
+            impact_vel_b,
+            manifold,
+        };
+
+        // Pairs are always tracked, the flags only decide what gets sent.
+        if is_sensor {
+            if self.sensor_pairs.touch(event)
+                && active_events.contains(ActiveEvents::SENSOR_EVENTS)
+            {
+                events.handle_sensor_event(Self::sensor_event(
+                    SensorEventKind::Enter,
+                    &event,
+                    &self.col_set,
+                ));
             }
+        } else if self.contact_pairs.touch(event)
+            && active_events.contains(ActiveEvents::COLLISION_EVENTS)
+        {
+            events.handle_collision_event(event);
         }
 
-        perf_counter_inc("collisions", count);
+        true
+    }
+
+    /// Events enabled by either collider of a pair.
+    fn pair_events(&self, event: &CollisionEvent) -> ActiveEvents {
+        [event.col_handle_a, event.col_handle_b]
+            .into_iter()
+            .filter_map(|handle| self.col_set.get(handle))
+            .fold(ActiveEvents::empty(), |flags, collider| {
+                flags | collider.active_events
+            })
+    }
+
+    fn sensor_event(
+        kind: SensorEventKind,
+        event: &CollisionEvent,
+        col_set: &ColliderSet,
+    ) -> SensorEvent {
+        let (a, b) = (event.col_handle_a, event.col_handle_b);
+        let a_is_sensor = match col_set.get(a) {
+            Some(col) => col.is_sensor(),
+            None => !col_set.get(b).is_some_and(|col| col.is_sensor()),
+        };
+
+        let (sensor, other) = if a_is_sensor { (a, b) } else { (b, a) };
+
+        SensorEvent {
+            kind,
+            sensor,
+            other,
+        }
+    }
+
+    /// Narrow phase test between two colliders including their margins.
+    fn collider_contact(col_a: &Collider, col_b: &Collider) -> Option<Contact> {
+        contact_shapes_with_margin(
+            col_a.shape(),
+            col_a.absolute_transform,
+            col_b.shape(),
+            col_b.absolute_transform,
+            col_a.margin + col_b.margin,
+        )
     }
 
+    /// Portion of the positional correction applied to `a`, the rest goes to `b`.
+    /// Static bodies never move, so the other body takes the full correction.
     fn mass_ratio(a: &RigidBody, b: &RigidBody) -> f32 {
-        1.0 - a.calculated_mass / (a.calculated_mass + b.calculated_mass)
+        if a.is_static() {
+            0.0
+        } else if b.is_static() {
+            1.0
+        } else {
+            1.0 - a.calculated_mass / (a.calculated_mass + b.calculated_mass)
+        }
     }
 
     fn update_objects(&mut self, dt: f32) {
         let _span = tracy_span!("update positions");
 
-        for (idx, body) in self.rbd_set.arena.iter_mut() {
+        for (_, body) in self.rbd_set.arena.iter_mut() {
             if body.is_static() {
                 body.position_old = body.position;
                 body.acceleration = Vec2::ZERO;
@@ -338,8 +600,6 @@ impl Physics {
             let displacement = (body.position - body.position_old) * (dt / self.old_dt);
             self.old_dt = dt;
 
-            self.spatial_hash.move_point(idx.to_bits(), displacement);
-
             body.position_old = body.position;
             body.position += displacement + body.acceleration * dt * dt;
 
@@ -394,7 +654,7 @@ impl Physics {
         }
     }
 
-    fn integrate(&mut self, substeps: u32, delta: f32) {
+    fn integrate(&mut self, substeps: u32, delta: f32, events: &mut dyn EventHandler) {
         let _span = tracy_span!("integrate");
         let step_delta = delta / substeps as f32;
 
@@ -408,17 +668,29 @@ impl Physics {
             }
 
             if self.collisions_enabled {
-                if self.use_spatial_hash {
-                    panic!("spatial collisions not supported right now");
-                } else {
-                    self.brute_force_collisions();
-                }
+                self.collisions(events);
             }
 
             self.solve_fixed_joints(step_delta);
             self.update_objects(step_delta);
             self.apply_constraints();
         }
+
+        for event in self.contact_pairs.finish_step() {
+            if self.pair_events(&event).contains(ActiveEvents::COLLISION_EVENTS) {
+                events.handle_collision_event(event);
+            }
+        }
+
+        for event in self.sensor_pairs.finish_step() {
+            if self.pair_events(&event).contains(ActiveEvents::SENSOR_EVENTS) {
+                events.handle_sensor_event(Self::sensor_event(
+                    SensorEventKind::Exit,
+                    &event,
+                    &self.col_set,
+                ));
+            }
+        }
     }
 
     fn solve_fixed_joints(&mut self, dt: f32) {
@@ -479,83 +751,4 @@ impl Physics {
     pub fn debug_data(&self) -> DebugData {
         make_debug_data(self)
     }
-
-    pub fn spatial_collisions(&mut self) {
-        panic!("spatial_collisions currently broken, use brute_force instead");
-        let _span = tracy_span!("spatial_collisions");
-
-        let keys = self.col_set.arena.iter().map(|(idx, _)| idx).collect_vec();
-        let mut count = 0;
-
-        for (_i, idx_a) in keys.iter().enumerate() {
-            let col_a = self.col_set.arena.get(*idx_a).unwrap();
-            let parent_a = col_a.parent.unwrap();
-            let rbd_a = self.rbd_set.arena.get(parent_a.0).unwrap();
-
-            const MAX_COLLIDER_RADIUS: f32 = 1.0;
-
-            let relevant_rigid_bodies = self
-                .spatial_hash
-                .query(rbd_a.position, col_a.radius + MAX_COLLIDER_RADIUS);
-
-            // for idx_b in keys.iter() {
-            //     let idx_b = *idx_b;
-            for cell_point in relevant_rigid_bodies {
-                let idx_b = Index::from_bits(cell_point.id).unwrap();
-
-                if let Some(col_b) = self.col_set.arena.get(idx_b) {
-                    if idx_a >= &idx_b {
-                        continue;
-                    }
-
-                    let parent_b = col_b.parent.unwrap();
-                    // let rbd_b =
-                    //     self.rbd_set.arena.get(parent_b.handle.0).unwrap();
-
-                    if !col_a.collision_groups.test(col_b.collision_groups) {
-                        continue;
-                    }
-
-                    let axis = col_a.absolute_translation() - col_b.absolute_translation();
-                    let distance = axis.length();
-                    let min_dist = col_a.radius + col_b.radius;
-
-                    if distance < min_dist {
-                        let parent_a_handle = parent_a.0;
-                        let parent_b_handle = parent_b.0;
-
-                        let (Some(rbd_a), Some(rbd_b)) = self
-                            .rbd_set
-                            .arena
-                            .get2_mut(parent_a_handle, parent_b_handle)
-                             else { continue; };
-
-                        if !col_a.flags.is_sensor && !col_b.flags.is_sensor {
-                            let n = axis / distance;
-                            let delta = min_dist - distance;
-
-                            let ratio = Self::mass_ratio(rbd_a, rbd_b);
-
-                            rbd_a.position += ratio * delta * n;
-                            rbd_b.position -= (1.0 - ratio) * delta * n;
-                        }
-
-                        count += 1;
-
-                        self.collision_send
-                            .send(CollisionEvent {
-                                col_handle_a: ColliderHandle(*idx_a),
-                                col_handle_b: ColliderHandle(idx_b),
-
-                                impact_vel_a: rbd_a.calculated_velocity,
-                                impact_vel_b: rbd_b.calculated_velocity,
-                            })
-                            .unwrap();
-                    }
-                }
-            }
-        }
-
-        perf_counter_inc("collisions", count);
-    }
 }
@@ -0,0 +1,154 @@
+use crate::*;
+
+/// Nearest point of a shape to a query point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PointProjection {
+    pub point: Vec2,
+    /// Whether the query point is inside the shape.
+    pub is_inside: bool,
+}
+
+impl PointProjection {
+    pub fn new(point: Vec2, is_inside: bool) -> Self {
+        Self { point, is_inside }
+    }
+}
+
+/// Nearest point to `point` on a chain of segments in world space, `None`
+/// for an empty chain.
+pub fn project_point_segments(points: &[Vec2], point: Vec2) -> Option<Vec2> {
+    points
+        .windows(2)
+        .map(|segment| closest_point_on_segment(segment[0], segment[1], point))
+        .min_by(|a, b| {
+            a.distance_squared(point)
+                .total_cmp(&b.distance_squared(point))
+        })
+        .or_else(|| points.first().copied())
+}
+
+/// Projects `point` onto a segment inflated by `radius`, which is a ball if
+/// both ends coincide.
+pub fn project_point_rounded_segment(
+    a: Vec2,
+    b: Vec2,
+    radius: f32,
+    point: Vec2,
+    solid: bool,
+) -> PointProjection {
+    let center = closest_point_on_segment(a, b, point);
+    let offset = point - center;
+    let distance = offset.length();
+    let is_inside = distance <= radius;
+
+    if is_inside && solid {
+        return PointProjection::new(point, true);
+    }
+
+    // A point right on the segment leaves sideways.
+    let direction = if distance > 1e-12 {
+        offset / distance
+    } else {
+        (b - a).perp().try_normalize().unwrap_or(Vec2::Y)
+    };
+
+    PointProjection::new(center + direction * radius, is_inside)
+}
+
+/// Projects `point` onto `shape` inflated by `margin`, the way colliders
+/// with a contact skin are seen by the rest of the pipeline.
+///
+/// The inflated shape's nearest point lies `margin` further out along the
+/// direction to the bare shape's nearest point.
+pub fn project_point_with_margin(
+    shape: &dyn Shape,
+    transform: Affine2,
+    margin: f32,
+    point: Vec2,
+    solid: bool,
+) -> Option<PointProjection> {
+    let projection = shape.project_point(transform, point, solid)?;
+
+    if margin <= 0.0 || (projection.is_inside && solid) {
+        return Some(projection);
+    }
+
+    let offset = projection.point - point;
+    let is_inside = projection.is_inside || offset.length() <= margin;
+
+    if is_inside && solid {
+        return Some(PointProjection::new(point, true));
+    }
+
+    let outward = if projection.is_inside {
+        offset
+    } else {
+        -offset
+    };
+
+    Some(PointProjection::new(
+        projection.point + outward.normalize_or_zero() * margin,
+        is_inside,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn points_project_onto_shapes() {
+        let at = |x: f32, y: f32| Affine2::from_translation(vec2(x, y));
+
+        let outside = Ball::new(1.0)
+            .project_point(at(1.0, 0.0), vec2(4.0, 0.0), true)
+            .unwrap();
+        assert_relative_eq!(outside.point, vec2(2.0, 0.0), epsilon = 1e-4);
+        assert!(!outside.is_inside);
+
+        let cuboid = Cuboid::new(vec2(1.0, 0.5));
+        let inside = cuboid
+            .project_point(at(0.0, 0.0), vec2(0.5, 0.25), true)
+            .unwrap();
+        assert_eq!(inside, PointProjection::new(vec2(0.5, 0.25), true));
+
+        // Hollow projections leave through the nearest side.
+        let inside = cuboid
+            .project_point(at(0.0, 0.0), vec2(0.5, 0.25), false)
+            .unwrap();
+        assert_relative_eq!(inside.point, vec2(0.5, 0.5), epsilon = 1e-4);
+        assert!(inside.is_inside);
+
+        let corner = cuboid
+            .project_point(at(0.0, 0.0), vec2(3.0, 3.0), true)
+            .unwrap();
+        assert_relative_eq!(corner.point, vec2(1.0, 0.5), epsilon = 1e-4);
+
+        let ground = Polyline::new(vec![vec2(-2.0, 0.0), vec2(2.0, 0.0)]);
+        let above = ground
+            .project_point(at(0.0, 0.0), vec2(1.0, 3.0), true)
+            .unwrap();
+        assert_eq!(above, PointProjection::new(vec2(1.0, 0.0), false));
+
+        let hills = Heightfield::new(vec![0.0, 1.0, 0.0], vec2(4.0, 1.0));
+        let below = hills
+            .project_point(at(0.0, 0.0), vec2(0.0, 0.5), false)
+            .unwrap();
+        assert!(below.is_inside);
+        assert_relative_eq!(below.point.y, 0.9, epsilon = 1e-4);
+        assert_relative_eq!(below.point.x.abs(), 0.2, epsilon = 1e-4);
+        assert!(
+            !hills
+                .project_point(at(0.0, 0.0), vec2(0.0, 1.5), true)
+                .unwrap()
+                .is_inside
+        );
+
+        assert_eq!(project_point_segments(&[], Vec2::ZERO), None);
+        assert_eq!(
+            project_point_segments(&[vec2(1.0, 2.0)], Vec2::ZERO),
+            Some(vec2(1.0, 2.0))
+        );
+    }
+}
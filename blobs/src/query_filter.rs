@@ -6,9 +6,9 @@ bitflags::bitflags! {
     pub struct QueryFilterFlags: u32 {
         /// Exclude from the query any collider attached to a fixed rigid-body and colliders with no rigid-body attached.
         const EXCLUDE_FIXED = 1 << 1;
-        /// Exclude from the query any collider attached to a dynamic rigid-body.
-        const EXCLUDE_KINEMATIC = 1 << 2;
         /// Exclude from the query any collider attached to a kinematic rigid-body.
+        const EXCLUDE_KINEMATIC = 1 << 2;
+        /// Exclude from the query any collider attached to a dynamic rigid-body.
         const EXCLUDE_DYNAMIC = 1 << 3;
         /// Exclude from the query any collider that is a sensor.
         const EXCLUDE_SENSORS = 1 << 4;
@@ -28,43 +28,32 @@ impl QueryFilterFlags {
     /// Tests if the given collider should be taken into account by a scene query, based
     /// on the flags on `self`.
     #[inline]
-    pub fn test(&self, _bodies: &RigidBodySet, _collider: &Collider) -> bool {
-        todo!()
-        // if self.is_empty() {
-        //     // No filter.
-        //     return true;
-        // }
-        //
-        // if (self.contains(QueryFilterFlags::EXCLUDE_SENSORS)
-        //     && collider.is_sensor())
-        //     || (self.contains(QueryFilterFlags::EXCLUDE_SOLIDS)
-        //         && !collider.is_sensor())
-        // {
-        //     return false;
-        // }
-        //
-        // if self.contains(QueryFilterFlags::EXCLUDE_FIXED)
-        //     && collider.parent.is_none()
-        // {
-        //     return false;
-        // }
-        //
-        // if let Some(parent) = collider.parent.and_then(|p| bodies.get(p.handle))
-        // {
-        //     let parent_type = parent.body_type();
-        //
-        //     if (self.contains(QueryFilterFlags::EXCLUDE_FIXED)
-        //         && parent_type.is_fixed())
-        //         || (self.contains(QueryFilterFlags::EXCLUDE_KINEMATIC)
-        //             && parent_type.is_kinematic())
-        //         || (self.contains(QueryFilterFlags::EXCLUDE_DYNAMIC)
-        //             && parent_type.is_dynamic())
-        //     {
-        //         return false;
-        //     }
-        // }
-        //
-        // true
+    pub fn test(&self, bodies: &RigidBodySet, collider: &Collider) -> bool {
+        if self.is_empty() {
+            // No filter.
+            return true;
+        }
+
+        if (self.contains(QueryFilterFlags::EXCLUDE_SENSORS) && collider.is_sensor())
+            || (self.contains(QueryFilterFlags::EXCLUDE_SOLIDS) && !collider.is_sensor())
+        {
+            return false;
+        }
+
+        if self.contains(QueryFilterFlags::EXCLUDE_FIXED) && collider.parent.is_none() {
+            return false;
+        }
+
+        if let Some(parent) = collider.parent.and_then(|p| bodies.get(p)) {
+            if (self.contains(QueryFilterFlags::EXCLUDE_FIXED) && parent.is_static())
+                || (self.contains(QueryFilterFlags::EXCLUDE_KINEMATIC) && parent.is_kinematic())
+                || (self.contains(QueryFilterFlags::EXCLUDE_DYNAMIC) && parent.is_dynamic())
+            {
+                return false;
+            }
+        }
+
+        true
     }
 }
 
@@ -88,22 +77,17 @@ impl<'a> QueryFilter<'a> {
     /// Applies the filters described by `self` to a collider to determine if it has to be
     /// included in a scene query (`true`) or not (`false`).
     #[inline]
-    pub fn test(
-        &self,
-        _bodies: &RigidBodySet,
-        _handle: ColliderHandle,
-        _collider: &Collider,
-    ) -> bool {
-        todo!()
-        // self.exclude_collider != Some(handle)
-        //     && (self.exclude_rigid_body.is_none() // NOTE: deal with the `None` case separately otherwise the next test is incorrect if the collider’s parent is `None` too.
-        //         || self.exclude_rigid_body != collider.parent.map(|p| p.handle))
-        //     && self
-        //         .groups
-        //         .map(|grps| collider.flags.collision_groups.test(grps))
-        //         .unwrap_or(true)
-        //     && self.flags.test(bodies, collider)
-        //     && self.predicate.map(|f| f(handle, collider)).unwrap_or(true)
+    pub fn test(&self, bodies: &RigidBodySet, handle: ColliderHandle, collider: &Collider) -> bool {
+        self.exclude_collider != Some(handle)
+            // NOTE: deal with the `None` case separately otherwise the next test is incorrect
+            // if the collider's parent is `None` too.
+            && (self.exclude_rigid_body.is_none() || self.exclude_rigid_body != collider.parent)
+            && self
+                .groups
+                .map(|groups| collider.collision_groups.test(groups))
+                .unwrap_or(true)
+            && self.flags.test(bodies, collider)
+            && self.predicate.map(|f| f(handle, collider)).unwrap_or(true)
     }
 }
 
@@ -136,12 +120,12 @@ impl<'a> QueryFilter<'a> {
         QueryFilterFlags::EXCLUDE_FIXED.into()
     }
 
-    /// Exclude from the query any collider attached to a dynamic rigid-body.
+    /// Exclude from the query any collider attached to a kinematic rigid-body.
     pub fn exclude_kinematic() -> Self {
         QueryFilterFlags::EXCLUDE_KINEMATIC.into()
     }
 
-    /// Exclude from the query any collider attached to a kinematic rigid-body.
+    /// Exclude from the query any collider attached to a dynamic rigid-body.
     pub fn exclude_dynamic() -> Self {
         QueryFilterFlags::EXCLUDE_DYNAMIC.into()
     }
@@ -202,3 +186,76 @@ impl<'a> QueryFilter<'a> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_filter_excludes_colliders() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let mut insert = |body_type, is_sensor, groups| {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().body_type(body_type).build());
+            let collider = physics.insert_collider_with_parent(
+                ColliderBuilder::ball(0.5)
+                    .flags(ColliderFlags { is_sensor })
+                    .collision_groups(groups)
+                    .build(),
+                body,
+            );
+
+            (body, collider)
+        };
+
+        let dynamic = insert(RigidBodyType::Dynamic, false, InteractionGroups::all());
+        let fixed = insert(RigidBodyType::Static, false, InteractionGroups::all());
+        let kinematic = insert(
+            RigidBodyType::KinematicPositionBased,
+            false,
+            InteractionGroups::all(),
+        );
+        let sensor = insert(
+            RigidBodyType::Dynamic,
+            true,
+            groups(Group::GROUP_2, Group::GROUP_2),
+        );
+
+        let passing = |filter: QueryFilter| {
+            physics
+                .col_set
+                .iter()
+                .filter(|(handle, collider)| filter.test(&physics.rbd_set, *handle, collider))
+                .map(|(handle, _)| handle)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(passing(QueryFilter::new()).len(), 4);
+        assert_eq!(
+            passing(QueryFilter::only_dynamic()),
+            vec![dynamic.1, sensor.1]
+        );
+        assert_eq!(passing(QueryFilter::only_fixed()), vec![fixed.1]);
+        assert_eq!(passing(QueryFilter::only_kinematic()), vec![kinematic.1]);
+        assert_eq!(passing(QueryFilter::new().exclude_solids()), vec![sensor.1]);
+        assert_eq!(
+            passing(QueryFilter::only_dynamic().exclude_sensors()),
+            vec![dynamic.1]
+        );
+        assert_eq!(
+            passing(QueryFilter::new().groups(groups(Group::GROUP_1, Group::ALL))),
+            vec![dynamic.1, fixed.1, kinematic.1]
+        );
+
+        let filter = QueryFilter::only_dynamic()
+            .exclude_collider(sensor.1)
+            .exclude_rigid_body(fixed.0);
+        assert_eq!(passing(filter), vec![dynamic.1]);
+
+        let predicate = |handle, _: &Collider| handle != dynamic.1;
+        assert_eq!(
+            passing(QueryFilter::only_dynamic().predicate(&predicate)),
+            vec![sensor.1]
+        );
+    }
+}
@@ -0,0 +1,833 @@
+use crate::*;
+
+const PROJECT_POINT_MAX_ITERATIONS: usize = 256;
+
+/// Scene queries against the colliders of a `Physics` world.
+///
+/// Keeps its own BVH over collider bounds. `Physics` updates it at the end
+/// of every step and whenever colliders are inserted or removed, bodies
+/// moved by hand need `Physics::update_query_pipeline` before querying.
+pub struct QueryPipeline {
+    #[allow(dead_code)]
+    time_data: Rc<Cell<TimeData>>,
+    bvh: BvhBroadPhase,
+    /// Union of all collider AABBs, rays are clipped against it.
+    bounds: Option<AABB>,
+}
+
+impl QueryPipeline {
+    pub fn new(time_data: Rc<Cell<TimeData>>) -> Self {
+        Self {
+            time_data,
+            bvh: BvhBroadPhase::default(),
+            bounds: None,
+        }
+    }
+
+    /// Syncs the BVH with the current collider positions.
+    pub fn update(&mut self, col_set: &ColliderSet) {
+        self.bvh.update(col_set);
+
+        self.bounds = col_set
+            .iter()
+            .map(|(_, collider)| collider.calculate_aabb().grown(collider.margin))
+            .reduce(|mut bounds, aabb| {
+                bounds.expand_to_include_aabb(&aabb);
+                bounds
+            });
+    }
+
+    /// Makes a single collider queryable at its current position.
+    pub fn update_collider(&mut self, handle: ColliderHandle, collider: &Collider) {
+        self.bvh.update_collider(handle, collider);
+
+        let aabb = collider.calculate_aabb().grown(collider.margin);
+
+        self.bounds = Some(match self.bounds {
+            Some(mut bounds) => {
+                bounds.expand_to_include_aabb(&aabb);
+                bounds
+            }
+            None => aabb,
+        });
+    }
+
+    /// Stops returning `handle`, the world bounds only shrink on `update`.
+    pub fn remove_collider(&mut self, handle: ColliderHandle) {
+        self.bvh.remove(handle);
+    }
+
+    pub fn clear(&mut self) {
+        self.bvh.clear();
+        self.bounds = None;
+    }
+
+    /// First collider overlapping `shape` placed at `position`.
+    pub fn intersection_with_shape(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        position: &Vec2,
+        shape: &dyn Shape,
+        filter: QueryFilter,
+    ) -> Option<ColliderHandle> {
+        let mut result = None;
+
+        self.intersections_with_shape(rbd_set, col_set, position, shape, filter, |handle| {
+            result = Some(handle);
+            false
+        });
+
+        result
+    }
+
+    /// Calls `callback` with every collider overlapping `shape` placed at
+    /// `position`, until it returns `false`.
+    pub fn intersections_with_shape(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        position: &Vec2,
+        shape: &dyn Shape,
+        filter: QueryFilter,
+        mut callback: impl FnMut(ColliderHandle) -> bool,
+    ) {
+        let transform = Affine2::from_translation(*position);
+        let aabb = shape.calculate_aabb(transform);
+
+        for handle in self.bvh.overlapping(&aabb) {
+            let Some(collider) = col_set.get(handle) else {
+                continue;
+            };
+
+            if !filter.test(rbd_set, handle, collider) {
+                continue;
+            }
+
+            let contact = contact_shapes_with_margin(
+                shape,
+                transform,
+                collider.shape(),
+                collider.absolute_transform,
+                collider.margin,
+            );
+
+            if contact.is_some() && !callback(handle) {
+                return;
+            }
+        }
+    }
+
+    /// Closest collider hit by `ray` within `max_toi`, with its time of impact.
+    pub fn cast_ray(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, f32)> {
+        self.cast_ray_and_get_normal(rbd_set, col_set, ray, max_toi, solid, filter)
+            .map(|(handle, hit)| (handle, hit.time_of_impact))
+    }
+
+    /// Closest collider hit by `ray` within `max_toi`, with the hit point
+    /// and surface normal.
+    pub fn cast_ray_and_get_normal(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, RayIntersection)> {
+        let mut best: Option<(ColliderHandle, RayIntersection)> = None;
+        let mut max_toi = max_toi;
+
+        self.intersections_with_ray(
+            rbd_set,
+            col_set,
+            ray,
+            max_toi,
+            solid,
+            filter,
+            |handle, hit| {
+                if hit.time_of_impact < max_toi {
+                    max_toi = hit.time_of_impact;
+                    best = Some((handle, hit));
+                }
+
+                true
+            },
+        );
+
+        best
+    }
+
+    /// Calls `callback` with every collider hit by `ray` within `max_toi`,
+    /// margins included, in no particular order, until it returns `false`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn intersections_with_ray(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+        filter: QueryFilter,
+        mut callback: impl FnMut(ColliderHandle, RayIntersection) -> bool,
+    ) {
+        let Some(bounds) = self.bounds else { return };
+
+        // Clip the ray to the world so that long rays get a finite AABB.
+        let Some((enter, exit)) = ray_aabb_interval(&bounds, ray) else {
+            return;
+        };
+        let exit = exit.min(max_toi);
+
+        if enter > exit {
+            return;
+        }
+
+        let aabb = AABB::from_two_points(ray.point_at(enter), ray.point_at(exit));
+
+        for handle in self.bvh.overlapping(&aabb) {
+            let Some(collider) = col_set.get(handle) else {
+                continue;
+            };
+
+            if !filter.test(rbd_set, handle, collider) {
+                continue;
+            }
+
+            let hit = cast_ray_with_margin(
+                collider.shape(),
+                collider.absolute_transform,
+                collider.margin,
+                ray,
+                max_toi,
+                solid,
+            );
+
+            if let Some(hit) = hit {
+                if !callback(handle, hit) {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// First collider hit by `shape` moving from `position` along
+    /// `velocity`, colliders are treated as static during the sweep.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_shape(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        position: &Vec2,
+        velocity: &Vec2,
+        shape: &dyn Shape,
+        max_toi: f32,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, ShapeCastHit)> {
+        let bounds = self.bounds?;
+
+        let transform = Affine2::from_translation(*position);
+        let aabb = shape.calculate_aabb(transform);
+
+        // Clip the sweep to the world, grown so the shape's center path
+        // covers every contact.
+        let half_size = aabb.size() * 0.5;
+        let grown = AABB::new(bounds.min - half_size, bounds.max + half_size);
+        let path = Ray::new(aabb.center(), *velocity);
+
+        let (enter, exit) = ray_aabb_interval(&grown, &path)?;
+        let exit = exit.min(max_toi);
+
+        if enter > exit {
+            return None;
+        }
+
+        let mut swept = AABB::new(aabb.min + *velocity * enter, aabb.max + *velocity * enter);
+        swept.expand_to_include_aabb(&AABB::new(
+            aabb.min + *velocity * exit,
+            aabb.max + *velocity * exit,
+        ));
+
+        let mut best: Option<(ColliderHandle, ShapeCastHit)> = None;
+
+        for handle in self.bvh.overlapping(&swept) {
+            let Some(collider) = col_set.get(handle) else {
+                continue;
+            };
+
+            if !filter.test(rbd_set, handle, collider) {
+                continue;
+            }
+
+            let max_toi = best.map_or(max_toi, |(_, hit)| hit.time_of_impact);
+
+            let hit = cast_shape(
+                shape,
+                transform,
+                *velocity,
+                max_toi,
+                collider.shape(),
+                collider.absolute_transform,
+                collider.margin,
+            );
+
+            // Extrapolated hits can land slightly past `max_toi`.
+            if let Some(hit) = hit {
+                if best.is_none_or(|(_, best)| hit.time_of_impact < best.time_of_impact) {
+                    best = Some((handle, hit));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Collider nearest to `point` along with the projected point.
+    ///
+    /// Colliders are inflated by their margin. With `solid` a collider
+    /// containing `point` projects it onto itself, otherwise onto its boundary.
+    pub fn project_point(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        point: &Vec2,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, PointProjection)> {
+        let bounds = self.bounds?;
+
+        if !point.is_finite() {
+            return None;
+        }
+
+        let mut best: Option<(ColliderHandle, PointProjection, f32)> = None;
+        let mut half_size = (bounds.size().max_element() * 0.01).max(1e-3);
+
+        // Grow the search box until it holds a projection. Anything closer
+        // than the best one found then overlaps a box of that half size.
+        // Doubling covers any finite distance well within the iteration cap.
+        for _ in 0..PROJECT_POINT_MAX_ITERATIONS {
+            let aabb = AABB::from_center_size(*point, Vec2::splat(half_size * 2.0));
+
+            for handle in self.bvh.overlapping(&aabb) {
+                let Some(collider) = col_set.get(handle) else {
+                    continue;
+                };
+
+                if !filter.test(rbd_set, handle, collider) {
+                    continue;
+                }
+
+                let Some(projection) = project_point_with_margin(
+                    collider.shape(),
+                    collider.absolute_transform,
+                    collider.margin,
+                    *point,
+                    solid,
+                ) else {
+                    continue;
+                };
+
+                let distance = projection.point.distance(*point);
+
+                let is_closer = match best {
+                    Some((_, _, best_distance)) => distance < best_distance,
+                    None => true,
+                };
+
+                if is_closer {
+                    best = Some((handle, projection, distance));
+                }
+            }
+
+            match best {
+                Some((_, _, distance)) if distance <= half_size => break,
+                Some((_, _, distance)) => half_size = distance,
+                None if aabb.contains_aabb(&bounds) => break,
+                None => half_size *= 2.0,
+            }
+        }
+
+        best.map(|(handle, projection, _)| (handle, projection))
+    }
+
+    /// Calls `callback` with every collider containing `point`, margin
+    /// included, until it returns `false`.
+    pub fn intersections_with_point(
+        &self,
+        rbd_set: &RigidBodySet,
+        col_set: &ColliderSet,
+        point: &Vec2,
+        filter: QueryFilter,
+        mut callback: impl FnMut(ColliderHandle) -> bool,
+    ) {
+        let aabb = AABB::new(*point, *point);
+
+        for handle in self.bvh.overlapping(&aabb) {
+            let Some(collider) = col_set.get(handle) else {
+                continue;
+            };
+
+            if !filter.test(rbd_set, handle, collider) {
+                continue;
+            }
+
+            let is_inside = project_point_with_margin(
+                collider.shape(),
+                collider.absolute_transform,
+                collider.margin,
+                *point,
+                true,
+            )
+            .is_some_and(|projection| projection.is_inside);
+
+            if is_inside && !callback(handle) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn shape_intersections() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let ground = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .body_type(RigidBodyType::Static)
+                .build(),
+        );
+        let ground_col =
+            physics.insert_collider_with_parent(ColliderBuilder::cuboid(10.0, 0.5).build(), ground);
+
+        let balls = [-2.0, 0.0, 2.0, 6.0].map(|x| {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(x, 1.0)).build());
+            physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body)
+        });
+
+        physics.step(1.0 / 60.0, &mut NoopEventHandler);
+
+        let query = |position: Vec2, radius: f32, filter: QueryFilter| {
+            let mut hits = vec![];
+
+            physics.query_pipeline.intersections_with_shape(
+                &physics.rbd_set,
+                &physics.col_set,
+                &position,
+                &Ball::new(radius),
+                filter,
+                |handle| {
+                    hits.push(handle);
+                    true
+                },
+            );
+
+            hits.sort_by_key(|handle| handle.0);
+            hits
+        };
+
+        assert_eq!(
+            query(vec2(0.0, 1.0), 2.0, QueryFilter::only_dynamic()),
+            vec![balls[0], balls[1], balls[2]]
+        );
+        assert_eq!(
+            query(vec2(5.2, 1.5), 0.6, QueryFilter::new()),
+            vec![balls[3]]
+        );
+        assert!(query(vec2(0.0, 5.0), 1.0, QueryFilter::new()).is_empty());
+
+        let first = physics.query_pipeline.intersection_with_shape(
+            &physics.rbd_set,
+            &physics.col_set,
+            &vec2(4.0, 0.0),
+            &Ball::new(0.2),
+            QueryFilter::new(),
+        );
+        assert_eq!(first, Some(ground_col));
+
+        let excluded = physics.query_pipeline.intersection_with_shape(
+            &physics.rbd_set,
+            &physics.col_set,
+            &vec2(4.0, 0.0),
+            &Ball::new(0.2),
+            QueryFilter::exclude_fixed(),
+        );
+        assert_eq!(excluded, None);
+    }
+
+    #[test]
+    fn ray_casts() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let balls = [2.0, 5.0, 8.0].map(|x| {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(x, 0.0)).build());
+            physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body)
+        });
+
+        physics.step(1.0 / 60.0, &mut NoopEventHandler);
+
+        let ray = Ray::new(Vec2::ZERO, Vec2::X);
+        let pipeline = &physics.query_pipeline;
+
+        let (handle, hit) = pipeline
+            .cast_ray_and_get_normal(
+                &physics.rbd_set,
+                &physics.col_set,
+                &ray,
+                f32::MAX,
+                true,
+                QueryFilter::new(),
+            )
+            .unwrap();
+
+        assert_eq!(handle, balls[0]);
+        assert_relative_eq!(hit.time_of_impact, 1.5, epsilon = 1e-4);
+        assert_relative_eq!(hit.normal.x, -1.0, epsilon = 1e-4);
+
+        let filter = QueryFilter::new().exclude_collider(balls[0]);
+        let first = pipeline.cast_ray(
+            &physics.rbd_set,
+            &physics.col_set,
+            &ray,
+            100.0,
+            true,
+            filter,
+        );
+        assert_eq!(first.map(|(handle, _)| handle), Some(balls[1]));
+
+        let short = pipeline.cast_ray(&physics.rbd_set, &physics.col_set, &ray, 1.0, true, filter);
+        assert_eq!(short, None);
+
+        let mut hits = vec![];
+        pipeline.intersections_with_ray(
+            &physics.rbd_set,
+            &physics.col_set,
+            &ray,
+            f32::MAX,
+            true,
+            QueryFilter::new(),
+            |handle, _| {
+                hits.push(handle);
+                true
+            },
+        );
+
+        hits.sort_by_key(|handle| handle.0);
+        assert_eq!(hits, balls.to_vec());
+    }
+
+    #[test]
+    fn ray_casts_include_margin() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let body = physics.insert_rbd(RigidBodyBuilder::new().build());
+        let col = physics.insert_collider_with_parent(
+            ColliderBuilder::cuboid(1.0, 1.0).margin(0.25).build(),
+            body,
+        );
+
+        let cast = |origin: Vec2, solid: bool| {
+            physics.query_pipeline.cast_ray_and_get_normal(
+                &physics.rbd_set,
+                &physics.col_set,
+                &Ray::new(origin, Vec2::X),
+                f32::MAX,
+                solid,
+                QueryFilter::new(),
+            )
+        };
+
+        let (handle, hit) = cast(vec2(-3.0, 0.0), true).unwrap();
+        assert_eq!(handle, col);
+        assert_relative_eq!(hit.time_of_impact, 1.75, epsilon = 1e-3);
+        assert_relative_eq!(hit.normal, vec2(-1.0, 0.0), epsilon = 1e-3);
+
+        // The margin rounds the corners.
+        let (_, hit) = cast(vec2(-3.0, 1.2), true).unwrap();
+        assert_relative_eq!(hit.time_of_impact, 1.85, epsilon = 1e-3);
+        assert_relative_eq!(hit.normal, vec2(-0.6, 0.8), epsilon = 1e-2);
+
+        assert!(cast(vec2(-3.0, 1.3), true).is_none());
+
+        let (_, hit) = cast(vec2(1.1, 0.0), true).unwrap();
+        assert_eq!(hit.time_of_impact, 0.0);
+        assert_eq!(hit.normal, Vec2::ZERO);
+
+        let (_, hit) = cast(Vec2::ZERO, false).unwrap();
+        assert_relative_eq!(hit.time_of_impact, 1.25, epsilon = 1e-3);
+        assert_relative_eq!(hit.normal, vec2(1.0, 0.0), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn shape_casts() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let walls = [3.0, 6.0].map(|x| {
+            let body = physics.insert_rbd(
+                RigidBodyBuilder::new()
+                    .body_type(RigidBodyType::Static)
+                    .position(vec2(x, 0.0))
+                    .build(),
+            );
+            physics.insert_collider_with_parent(ColliderBuilder::cuboid(0.5, 2.0).build(), body)
+        });
+
+        physics.step(1.0 / 60.0, &mut NoopEventHandler);
+
+        let cast = |filter: QueryFilter| {
+            physics.query_pipeline.cast_shape(
+                &physics.rbd_set,
+                &physics.col_set,
+                &Vec2::ZERO,
+                &vec2(1.0, 0.0),
+                &Ball::new(0.5),
+                f32::MAX,
+                filter,
+            )
+        };
+
+        let (handle, hit) = cast(QueryFilter::new()).unwrap();
+        assert_eq!(handle, walls[0]);
+        assert_relative_eq!(hit.time_of_impact, 2.0, epsilon = 1e-3);
+        assert_relative_eq!(hit.normal, vec2(-1.0, 0.0), epsilon = 1e-3);
+
+        let (handle, hit) = cast(QueryFilter::new().exclude_collider(walls[0])).unwrap();
+        assert_eq!(handle, walls[1]);
+        assert_relative_eq!(hit.time_of_impact, 5.0, epsilon = 1e-3);
+
+        assert!(cast(QueryFilter::exclude_fixed()).is_none());
+    }
+
+    #[test]
+    fn shape_casts_keep_the_nearest_hit() {
+        // Both walls are within the cast tolerance of each other, whichever
+        // is visited first the nearer one has to win.
+        for offsets in [[0.0, 5e-4], [5e-4, 0.0]] {
+            let mut physics = Physics::new(Vec2::ZERO);
+
+            let walls = offsets.map(|offset| {
+                let body = physics.insert_rbd(
+                    RigidBodyBuilder::new()
+                        .body_type(RigidBodyType::Static)
+                        .position(vec2(2.5 + offset, 0.0))
+                        .build(),
+                );
+                physics.insert_collider_with_parent(ColliderBuilder::cuboid(0.5, 2.0).build(), body)
+            });
+
+            let nearest = if offsets[0] < offsets[1] {
+                walls[0]
+            } else {
+                walls[1]
+            };
+
+            let (handle, hit) = physics
+                .query_pipeline
+                .cast_shape(
+                    &physics.rbd_set,
+                    &physics.col_set,
+                    &Vec2::ZERO,
+                    &vec2(1.0, 0.0),
+                    &Ball::new(0.5),
+                    f32::MAX,
+                    QueryFilter::new(),
+                )
+                .unwrap();
+
+            assert_eq!(handle, nearest);
+            assert_relative_eq!(hit.time_of_impact, 1.5, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn point_queries() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let ground = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .body_type(RigidBodyType::Static)
+                .build(),
+        );
+        let ground_col =
+            physics.insert_collider_with_parent(ColliderBuilder::cuboid(10.0, 0.5).build(), ground);
+
+        let balls = [-2.0, 0.0, 20.0].map(|x| {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(x, 1.0)).build());
+            physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body)
+        });
+
+        physics.step(1.0 / 60.0, &mut NoopEventHandler);
+
+        let containing = |point: Vec2, filter: QueryFilter| {
+            let mut hits = vec![];
+
+            physics.query_pipeline.intersections_with_point(
+                &physics.rbd_set,
+                &physics.col_set,
+                &point,
+                filter,
+                |handle| {
+                    hits.push(handle);
+                    true
+                },
+            );
+
+            hits
+        };
+
+        assert_eq!(
+            containing(vec2(-2.0, 1.2), QueryFilter::new()),
+            vec![balls[0]]
+        );
+        assert_eq!(
+            containing(vec2(0.1, 0.0), QueryFilter::new()),
+            vec![ground_col]
+        );
+        assert!(containing(vec2(-1.0, 1.2), QueryFilter::new()).is_empty());
+        assert!(containing(vec2(0.1, 0.0), QueryFilter::exclude_fixed()).is_empty());
+
+        let project = |point: Vec2, solid: bool| {
+            physics.query_pipeline.project_point(
+                &physics.rbd_set,
+                &physics.col_set,
+                &point,
+                solid,
+                QueryFilter::new(),
+            )
+        };
+
+        let (handle, projection) = project(vec2(0.0, 3.0), true).unwrap();
+        assert_eq!(handle, balls[1]);
+        assert_relative_eq!(projection.point, vec2(0.0, 1.5), epsilon = 1e-3);
+        assert!(!projection.is_inside);
+
+        // Far from everything, the search has to widen to find the ball.
+        let (handle, projection) = project(vec2(40.0, 1.0), true).unwrap();
+        assert_eq!(handle, balls[2]);
+        assert_relative_eq!(projection.point, vec2(20.5, 1.0), epsilon = 1e-3);
+
+        let (handle, projection) = project(vec2(5.0, 0.0), false).unwrap();
+        assert_eq!(handle, ground_col);
+        assert_relative_eq!(projection.point, vec2(5.0, 0.5), epsilon = 1e-3);
+        assert!(projection.is_inside);
+
+        assert_eq!(project(vec2(f32::NAN, 0.0), true), None);
+        assert_eq!(project(Vec2::splat(f32::INFINITY), false), None);
+    }
+
+    #[test]
+    fn point_queries_include_margin() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let body = physics.insert_rbd(RigidBodyBuilder::new().build());
+        let col = physics.insert_collider_with_parent(
+            ColliderBuilder::cuboid(1.0, 1.0).margin(0.25).build(),
+            body,
+        );
+
+        let is_inside = |point: Vec2| {
+            let mut hits = vec![];
+
+            physics.query_pipeline.intersections_with_point(
+                &physics.rbd_set,
+                &physics.col_set,
+                &point,
+                QueryFilter::new(),
+                |handle| {
+                    hits.push(handle);
+                    true
+                },
+            );
+
+            hits == vec![col]
+        };
+
+        // Shape queries already treat the margin as part of the collider.
+        assert!(physics
+            .query_pipeline
+            .intersection_with_shape(
+                &physics.rbd_set,
+                &physics.col_set,
+                &vec2(1.2, 0.0),
+                &Ball::new(0.01),
+                QueryFilter::new(),
+            )
+            .is_some());
+        assert!(is_inside(vec2(1.2, 0.0)));
+        assert!(!is_inside(vec2(1.3, 0.0)));
+
+        let project = |point: Vec2, solid: bool| {
+            physics
+                .query_pipeline
+                .project_point(
+                    &physics.rbd_set,
+                    &physics.col_set,
+                    &point,
+                    solid,
+                    QueryFilter::new(),
+                )
+                .unwrap()
+                .1
+        };
+
+        let projection = project(vec2(3.0, 0.0), true);
+        assert_relative_eq!(projection.point, vec2(1.25, 0.0), epsilon = 1e-3);
+        assert!(!projection.is_inside);
+
+        let projection = project(vec2(1.1, 0.0), false);
+        assert_relative_eq!(projection.point, vec2(1.25, 0.0), epsilon = 1e-3);
+        assert!(projection.is_inside);
+
+        let projection = project(vec2(0.0, 0.8), false);
+        assert_relative_eq!(projection.point, vec2(0.0, 1.25), epsilon = 1e-3);
+        assert!(projection.is_inside);
+
+        let projection = project(vec2(1.1, 0.0), true);
+        assert_relative_eq!(projection.point, vec2(1.1, 0.0));
+        assert!(projection.is_inside);
+    }
+
+    #[test]
+    fn queries_see_colliders_before_stepping() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let spawn = |physics: &mut Physics, position: Vec2| {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(position).build());
+            physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body)
+        };
+
+        let is_free = |physics: &Physics, position: Vec2| {
+            physics
+                .query_pipeline
+                .intersection_with_shape(
+                    &physics.rbd_set,
+                    &physics.col_set,
+                    &position,
+                    &Ball::new(0.5),
+                    QueryFilter::new(),
+                )
+                .is_none()
+        };
+
+        // Two spawns in the same frame don't end up on top of each other.
+        assert!(is_free(&physics, vec2(1.0, 1.0)));
+        let first = spawn(&mut physics, vec2(1.0, 1.0));
+        assert!(!is_free(&physics, vec2(1.5, 1.0)));
+
+        physics.remove_col(first);
+        assert!(is_free(&physics, vec2(1.5, 1.0)));
+    }
+}
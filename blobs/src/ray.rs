@@ -0,0 +1,413 @@
+use crate::*;
+
+/// A half-line starting at `origin`, `dir` doesn't need to be normalized.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec2,
+    pub dir: Vec2,
+}
+
+impl Ray {
+    pub fn new(origin: Vec2, dir: Vec2) -> Self {
+        Self { origin, dir }
+    }
+
+    pub fn point_at(&self, toi: f32) -> Vec2 {
+        self.origin + self.dir * toi
+    }
+
+    pub fn transformed(&self, transform: Affine2) -> Ray {
+        Ray::new(
+            transform.transform_point2(self.origin),
+            transform.transform_vector2(self.dir),
+        )
+    }
+}
+
+/// Where a ray hits a shape.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayIntersection {
+    /// Hit point is at `ray.origin + ray.dir * time_of_impact`.
+    pub time_of_impact: f32,
+    pub point: Vec2,
+    /// Outward surface normal at the hit point, zero for solid hits of a
+    /// ray starting inside the shape.
+    pub normal: Vec2,
+}
+
+/// Entry time and outward local normal of a ray against a convex shape,
+/// `(0.0, Vec2::ZERO)` if it starts inside.
+type RayEntry = Option<(f32, Vec2)>;
+
+/// Casts a world space ray against a convex shape through its local space
+/// `entry` routine.
+///
+/// Affine maps keep the ray parameter, so the time of impact carries over
+/// from local space unchanged. Non-solid rays starting inside are cast
+/// backwards from beyond the shape to find where they leave it.
+pub fn cast_ray_convex(
+    transform: Affine2,
+    local_aabb: AABB,
+    ray: &Ray,
+    max_toi: f32,
+    solid: bool,
+    entry: impl Fn(&Ray) -> RayEntry,
+) -> Option<RayIntersection> {
+    let local_ray = ray.transformed(transform.inverse());
+    let (mut toi, mut normal) = entry(&local_ray)?;
+
+    if toi == 0.0 && normal == Vec2::ZERO && !solid {
+        let dir_length = local_ray.dir.length();
+
+        if dir_length < 1e-12 {
+            return None;
+        }
+
+        let reach = (local_ray.origin.distance(local_aabb.center()) + local_aabb.size().length())
+            / dir_length;
+
+        let reversed = Ray::new(local_ray.point_at(reach), -local_ray.dir);
+        let (back_toi, back_normal) = entry(&reversed)?;
+
+        toi = reach - back_toi;
+        normal = back_normal;
+    }
+
+    if toi > max_toi {
+        return None;
+    }
+
+    Some(RayIntersection {
+        time_of_impact: toi,
+        point: ray.point_at(toi),
+        normal: local_to_world_normal(transform, normal),
+    })
+}
+
+/// Casts `ray` against `shape` inflated by `margin`.
+///
+/// Inflated shapes have no closed form, so the ray is swept as a point
+/// through `cast_shape`, which handles target margins exactly.
+pub fn cast_ray_with_margin(
+    shape: &dyn Shape,
+    transform: Affine2,
+    margin: f32,
+    ray: &Ray,
+    max_toi: f32,
+    solid: bool,
+) -> Option<RayIntersection> {
+    if margin <= 0.0 {
+        return shape.cast_ray(transform, ray, max_toi, solid);
+    }
+
+    let point = Ball::new(0.0);
+    let cast = |origin: Vec2, dir: Vec2, max_toi: f32| {
+        let start = Affine2::from_translation(origin);
+        cast_shape(&point, start, dir, max_toi, shape, transform, margin)
+    };
+
+    let origin = Affine2::from_translation(ray.origin);
+    let starts_inside =
+        contact_shapes_with_margin(&point, origin, shape, transform, margin).is_some();
+
+    let (toi, normal) = match (starts_inside, solid) {
+        (false, _) => {
+            let hit = cast(ray.origin, ray.dir, max_toi)?;
+            (hit.time_of_impact, hit.normal)
+        }
+        (true, true) => (0.0, Vec2::ZERO),
+        // Cast backwards from beyond the shape to find where the ray leaves.
+        (true, false) => {
+            let dir_length = ray.dir.length();
+
+            if dir_length < 1e-12 {
+                return None;
+            }
+
+            let aabb = shape.calculate_aabb(transform).grown(margin);
+            let reach = (ray.origin.distance(aabb.center()) + aabb.size().length()) / dir_length;
+
+            let hit = cast(ray.point_at(reach), -ray.dir, reach)?;
+            (reach - hit.time_of_impact, hit.normal)
+        }
+    };
+
+    if toi > max_toi {
+        return None;
+    }
+
+    Some(RayIntersection {
+        time_of_impact: toi,
+        point: ray.point_at(toi),
+        normal,
+    })
+}
+
+/// Normals transform with the inverse transpose to stay perpendicular to
+/// surfaces under non-uniform scale.
+fn local_to_world_normal(transform: Affine2, normal: Vec2) -> Vec2 {
+    (transform.matrix2.inverse().transpose() * normal).normalize_or_zero()
+}
+
+pub fn ray_entry_ball(radius: f32, ray: &Ray) -> RayEntry {
+    let a = ray.dir.length_squared();
+    let b = ray.origin.dot(ray.dir);
+    let c = ray.origin.length_squared() - radius * radius;
+
+    if c <= 0.0 {
+        return Some((0.0, Vec2::ZERO));
+    }
+
+    let discriminant = b * b - a * c;
+
+    if a < 1e-12 || b > 0.0 || discriminant < 0.0 {
+        return None;
+    }
+
+    let toi = (-b - discriminant.sqrt()) / a;
+
+    Some((toi, ray.point_at(toi) / radius))
+}
+
+/// Slab test against an axis aligned box, returning the entry time, exit
+/// time and entry normal of the infinite line.
+fn ray_slabs(aabb: &AABB, ray: &Ray) -> Option<(f32, f32, Vec2)> {
+    let mut enter = f32::MIN;
+    let mut exit = f32::MAX;
+    let mut normal = Vec2::ZERO;
+
+    for axis in [Vec2::X, Vec2::Y] {
+        let origin = ray.origin.dot(axis);
+        let dir = ray.dir.dot(axis);
+        let (min, max) = (aabb.min.dot(axis), aabb.max.dot(axis));
+
+        if dir.abs() < 1e-12 {
+            if origin < min || origin > max {
+                return None;
+            }
+
+            continue;
+        }
+
+        let (near, far) = if dir > 0.0 { (min, max) } else { (max, min) };
+        let t_near = (near - origin) / dir;
+        let t_far = (far - origin) / dir;
+
+        if t_near > enter {
+            enter = t_near;
+            normal = -axis * dir.signum();
+        }
+
+        exit = exit.min(t_far);
+    }
+
+    if enter > exit || exit < 0.0 {
+        return None;
+    }
+
+    Some((enter, exit, normal))
+}
+
+pub fn ray_entry_aabb(aabb: &AABB, ray: &Ray) -> RayEntry {
+    let (enter, _, normal) = ray_slabs(aabb, ray)?;
+
+    if enter <= 0.0 {
+        return Some((0.0, Vec2::ZERO));
+    }
+
+    Some((enter, normal))
+}
+
+/// Range of times during which `ray` is inside `aabb`.
+pub fn ray_aabb_interval(aabb: &AABB, ray: &Ray) -> Option<(f32, f32)> {
+    let (enter, exit, _) = ray_slabs(aabb, ray)?;
+    Some((enter.max(0.0), exit))
+}
+
+/// Clips a ray against the edges of a counter-clockwise convex polygon.
+pub fn ray_entry_polygon(points: &[Vec2], ray: &Ray) -> RayEntry {
+    let mut enter = f32::MIN;
+    let mut exit = f32::MAX;
+    let mut normal = Vec2::ZERO;
+
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let outward = vec2(b.y - a.y, a.x - b.x).normalize_or_zero();
+
+        let distance = outward.dot(a - ray.origin);
+        let speed = outward.dot(ray.dir);
+
+        if speed.abs() < 1e-12 {
+            if distance < 0.0 {
+                return None;
+            }
+
+            continue;
+        }
+
+        let t = distance / speed;
+
+        if speed < 0.0 {
+            if t > enter {
+                enter = t;
+                normal = outward;
+            }
+        } else {
+            exit = exit.min(t);
+        }
+    }
+
+    if enter > exit || exit < 0.0 {
+        return None;
+    }
+
+    if enter <= 0.0 {
+        return Some((0.0, Vec2::ZERO));
+    }
+
+    Some((enter, normal))
+}
+
+/// The capsule is the union of its end caps and the box between them.
+pub fn ray_entry_capsule(capsule: &Capsule, ray: &Ray) -> RayEntry {
+    let [a, b] = capsule.local_segment();
+    let radius = capsule.radius;
+
+    if closest_point_on_segment(a, b, ray.origin).distance_squared(ray.origin) <= radius * radius {
+        return Some((0.0, Vec2::ZERO));
+    }
+
+    let middle = AABB::new(vec2(-radius, a.y), vec2(radius, b.y));
+
+    [a, b]
+        .into_iter()
+        .filter_map(|center| ray_entry_ball(radius, &Ray::new(ray.origin - center, ray.dir)))
+        .chain(ray_entry_aabb(&middle, ray))
+        .min_by(|x, y| x.0.total_cmp(&y.0))
+}
+
+/// First hit against a chain of segments in world space, skipping segments
+/// hit from behind if `one_sided`.
+pub fn cast_ray_segments(
+    points: &[Vec2],
+    one_sided: bool,
+    ray: &Ray,
+    max_toi: f32,
+) -> Option<RayIntersection> {
+    let mut best: Option<RayIntersection> = None;
+
+    for segment in points.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let edge = b - a;
+        let denominator = ray.dir.perp_dot(edge);
+
+        if denominator.abs() < 1e-12 {
+            continue;
+        }
+
+        let to_start = a - ray.origin;
+        let toi = to_start.perp_dot(edge) / denominator;
+        let along = to_start.perp_dot(ray.dir) / denominator;
+
+        if toi < 0.0 || toi > max_toi || !(0.0..=1.0).contains(&along) {
+            continue;
+        }
+
+        let front = edge.perp().normalize_or_zero();
+
+        if one_sided && front.dot(ray.dir) > 0.0 {
+            continue;
+        }
+
+        let is_closer = match best {
+            Some(best) => toi < best.time_of_impact,
+            None => true,
+        };
+
+        if is_closer {
+            let normal = if front.dot(ray.dir) > 0.0 {
+                -front
+            } else {
+                front
+            };
+
+            best = Some(RayIntersection {
+                time_of_impact: toi,
+                point: ray.point_at(toi),
+                normal,
+            });
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn ray_casts_against_shapes() {
+        let ray = Ray::new(vec2(-5.0, 0.0), vec2(2.0, 0.0));
+        let at = |x: f32, y: f32| Affine2::from_translation(vec2(x, y));
+
+        let hit = Ball::new(1.0)
+            .cast_ray(at(0.0, 0.0), &ray, 10.0, true)
+            .unwrap();
+        assert_relative_eq!(hit.time_of_impact, 2.0);
+        assert_relative_eq!(hit.point, vec2(-1.0, 0.0));
+        assert_relative_eq!(hit.normal, vec2(-1.0, 0.0));
+
+        // Too far away for `max_toi`.
+        assert!(Ball::new(1.0)
+            .cast_ray(at(0.0, 0.0), &ray, 1.5, true)
+            .is_none());
+
+        let rotated = Affine2::from_angle_translation(FRAC_PI_4, vec2(1.0, 0.0));
+        let hit = Cuboid::new(vec2(1.0, 1.0))
+            .cast_ray(rotated, &ray, 10.0, true)
+            .unwrap();
+        assert_relative_eq!(hit.point.x, 1.0 - 2.0f32.sqrt(), epsilon = 1e-5);
+
+        let triangle = ConvexPolygon::new(vec![vec2(0.0, -1.0), vec2(1.0, 1.0), vec2(-1.0, 1.0)]);
+        let hit = triangle.cast_ray(at(0.0, 0.0), &ray, 10.0, true).unwrap();
+        assert_relative_eq!(hit.point, vec2(-0.5, 0.0), epsilon = 1e-5);
+        assert_relative_eq!(hit.normal, vec2(-2.0, -1.0).normalize(), epsilon = 1e-5);
+
+        let capsule = Capsule::new(1.0, 0.5);
+        let down = Ray::new(vec2(0.0, 5.0), vec2(0.0, -1.0));
+        let hit = capsule.cast_ray(at(0.0, 0.0), &down, 10.0, true).unwrap();
+        assert_relative_eq!(hit.time_of_impact, 3.5, epsilon = 1e-5);
+        assert_relative_eq!(hit.normal, vec2(0.0, 1.0), epsilon = 1e-5);
+
+        let hit = capsule.cast_ray(at(-5.0, 0.5), &ray, 10.0, true).unwrap();
+        assert_relative_eq!(hit.time_of_impact, 0.0);
+        assert_eq!(hit.normal, Vec2::ZERO);
+
+        // Hollow casts from inside hit the boundary on the way out.
+        let hit = capsule.cast_ray(at(-5.0, 0.5), &ray, 10.0, false).unwrap();
+        assert_relative_eq!(hit.time_of_impact, 0.25, epsilon = 1e-4);
+        assert_relative_eq!(hit.normal, vec2(1.0, 0.0), epsilon = 1e-4);
+
+        let stretched = Affine2::from_scale(vec2(2.0, 1.0));
+        let hit = Ball::new(1.0)
+            .cast_ray(stretched, &ray, 10.0, true)
+            .unwrap();
+        assert_relative_eq!(hit.point, vec2(-2.0, 0.0), epsilon = 1e-5);
+
+        let ground = Polyline::new(vec![vec2(-2.0, 0.0), vec2(2.0, 0.0)]).one_sided(true);
+        let hit = ground.cast_ray(at(0.0, 0.0), &down, 10.0, true).unwrap();
+        assert_relative_eq!(hit.point, vec2(0.0, 0.0));
+        assert_relative_eq!(hit.normal, vec2(0.0, 1.0));
+
+        let up = Ray::new(vec2(0.0, -5.0), vec2(0.0, 1.0));
+        assert!(ground.cast_ray(at(0.0, 0.0), &up, 10.0, true).is_none());
+
+        let hills = Heightfield::new(vec![0.0, 1.0, 0.0], vec2(4.0, 1.0));
+        let hit = hills.cast_ray(at(0.0, 0.0), &down, 10.0, true).unwrap();
+        assert_relative_eq!(hit.point, vec2(0.0, 1.0));
+        assert!(hills.cast_ray(at(0.0, 0.0), &up, 10.0, true).is_some());
+    }
+}
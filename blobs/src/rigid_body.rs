@@ -86,7 +86,7 @@ impl RigidBody {
     }
 
     pub fn transform(&self) -> Affine2 {
-        Affine2::from_angle_translation(self.rotation, self.position)
+        Affine2::from_scale_angle_translation(self.scale, self.rotation, self.position)
     }
 
     pub fn translation(&self) -> Vec2 {
@@ -94,10 +94,7 @@ impl RigidBody {
     }
 
     pub fn update_mass_and_inertia(&mut self, col_set: &ColliderSet) {
-        self.calculated_mass = 0.0;
-        self.inertia = 0.0;
-
-        let mut weighted_centers = Vec2::ZERO;
+        let mut parts = Vec::with_capacity(self.colliders.len());
 
         for col_handle in self.colliders.iter() {
             if let Some(collider) = &col_set.get(*col_handle) {
@@ -105,26 +102,39 @@ impl RigidBody {
                     continue;
                 }
 
-                let mass = collider.mass();
-
-                self.calculated_mass += mass;
-                self.inertia += collider.inertia();
-
-                weighted_centers += collider.offset.translation * mass;
+                parts.push(collider.scaled_mass_properties(self.scale));
             } else {
                 eprintln!("Collider {:?} not found in collider set", col_handle);
             }
         }
 
-        if self.calculated_mass == 0.0 {
-            self.calculated_mass = 1.0;
-        }
+        let mass: f32 = parts.iter().map(|part| part.mass).sum();
+
+        self.center_of_mass = if mass > 0.0 {
+            parts
+                .iter()
+                .map(|part| part.local_center_of_mass * part.mass)
+                .sum::<Vec2>()
+                / mass
+        } else {
+            Vec2::ZERO
+        };
+
+        // Parallel axis theorem to move every part's inertia onto the body's
+        // center of mass.
+        self.inertia = parts
+            .iter()
+            .map(|part| {
+                part.inertia
+                    + part.mass * part.local_center_of_mass.distance_squared(self.center_of_mass)
+            })
+            .sum();
+
+        self.calculated_mass = if mass > 0.0 { mass } else { 1.0 };
 
         if self.inertia == 0.0 {
             self.inertia = 1.0;
         }
-
-        self.center_of_mass = weighted_centers / self.calculated_mass;
     }
 
     pub fn apply_impulse(&mut self, impulse: Vec2) {
@@ -242,14 +252,16 @@ pub enum RigidBodyType {
 }
 
 pub struct RigidBodySet {
-    time_data: Rc<TimeData>,
+    time_data: Rc<Cell<TimeData>>,
+    event_history: Rc<RefCell<EventHistory>>,
     pub arena: Arena<RigidBody>,
 }
 
 impl RigidBodySet {
-    pub fn new(time_data: Rc<TimeData>) -> Self {
+    pub fn new(time_data: Rc<Cell<TimeData>>, event_history: Rc<RefCell<EventHistory>>) -> Self {
         Self {
             time_data,
+            event_history,
             arena: Arena::new(),
         }
     }
@@ -264,8 +276,8 @@ impl RigidBodySet {
 
     pub fn remove_rbd(&mut self, handle: RigidBodyHandle) {
         if self.arena.remove(handle.0).is_none() {
-            push_event(PhysicsEvent {
-                time_data: *self.time_data,
+            self.event_history.borrow_mut().push(PhysicsEvent {
+                time_data: self.time_data.get(),
                 position: None,
                 message: "removing a non-existent rigid body".into(),
                 severity: Severity::Error,
@@ -0,0 +1,161 @@
+use crate::*;
+
+#[derive(Copy, Clone, Debug)]
+struct SapEntry {
+    handle: ColliderHandle,
+    aabb: AABB,
+    seen: bool,
+}
+
+/// Incremental sort and sweep over the x axis of collider AABBs.
+///
+/// The sorted order is kept between steps, so an insertion sort only has to
+/// fix the few entries that moved past each other. Suits wide, mostly
+/// horizontal scenes.
+#[derive(Clone, Debug, Default)]
+pub struct SweepAndPrune {
+    entries: Vec<SapEntry>,
+    indices: HashMap<ColliderHandle, usize>,
+}
+
+impl SweepAndPrune {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn remove(&mut self, handle: ColliderHandle) {
+        if let Some(index) = self.indices.remove(&handle) {
+            self.entries.remove(index);
+
+            for entry in self.entries[index..].iter() {
+                *self.indices.get_mut(&entry.handle).unwrap() -= 1;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices.clear();
+    }
+
+    /// Refreshes the AABBs and restores the sorted order, dropping colliders
+    /// that no longer exist.
+    pub fn update(&mut self, col_set: &ColliderSet) {
+        for entry in self.entries.iter_mut() {
+            entry.seen = false;
+        }
+
+        for (handle, collider) in col_set.iter() {
+            let aabb = collider.calculate_aabb().grown(collider.margin);
+
+            match self.indices.get(&handle) {
+                Some(&index) => {
+                    self.entries[index].aabb = aabb;
+                    self.entries[index].seen = true;
+                }
+                None => self.entries.push(SapEntry {
+                    handle,
+                    aabb,
+                    seen: true,
+                }),
+            }
+        }
+
+        self.entries.retain(|entry| entry.seen);
+
+        // Nearly sorted from the previous step, insertion sort is close to O(n).
+        for i in 1..self.entries.len() {
+            let mut j = i;
+
+            while j > 0 && self.entries[j - 1].aabb.min.x > self.entries[j].aabb.min.x {
+                self.entries.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+
+        self.indices.clear();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            self.indices.insert(entry.handle, index);
+        }
+    }
+
+    /// Candidate pairs whose AABBs overlap, with the later collider first.
+    pub fn pairs(&self) -> Vec<ColliderPair> {
+        let mut pairs = vec![];
+
+        for (i, a) in self.entries.iter().enumerate() {
+            for b in self.entries[i + 1..].iter() {
+                if b.aabb.min.x > a.aabb.max.x {
+                    break;
+                }
+
+                if a.aabb.intersects(&b.aabb) {
+                    pairs.push(ordered_pair(a.handle, b.handle));
+                }
+            }
+        }
+
+        pairs
+    }
+}
+
+impl BroadPhase for SweepAndPrune {
+    fn find_pairs(&mut self, col_set: &ColliderSet) -> Vec<ColliderPair> {
+        self.update(col_set);
+        self.pairs()
+    }
+
+    fn remove(&mut self, handle: ColliderHandle) {
+        SweepAndPrune::remove(self, handle);
+    }
+
+    fn clear(&mut self) {
+        SweepAndPrune::clear(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sap_keeps_order_between_updates() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let mut colliders = vec![];
+
+        for x in [0.0, 3.0, 6.0] {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(x, 0.0)).build());
+            colliders.push(
+                physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body),
+            );
+        }
+
+        let mut sap = SweepAndPrune::new();
+
+        sap.update(&physics.col_set);
+        assert!(sap.pairs().is_empty());
+
+        // Move the first ball past the others, onto the last one.
+        physics.col_set.get_mut(colliders[0]).unwrap().absolute_transform =
+            Affine2::from_translation(vec2(6.5, 0.0));
+
+        sap.update(&physics.col_set);
+        assert_eq!(sap.pairs(), vec![(colliders[2], colliders[0])]);
+
+        physics.col_set.remove_ignoring_parent(colliders[2]);
+
+        sap.update(&physics.col_set);
+        assert_eq!(sap.len(), 2);
+        assert!(sap.pairs().is_empty());
+    }
+}
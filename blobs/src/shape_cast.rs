@@ -0,0 +1,321 @@
+use crate::*;
+
+const CAST_MAX_ITERATIONS: usize = 64;
+/// Gap at which a swept shape counts as touching its target.
+const CAST_TOLERANCE: f32 = 1e-3;
+
+/// First contact of a shape swept along a velocity.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShapeCastHit {
+    /// The shapes touch once the swept shape moved by
+    /// `velocity * time_of_impact`.
+    pub time_of_impact: f32,
+    /// Contact point on the swept shape at the time of impact.
+    pub witness_a: Vec2,
+    /// Contact point on the target, including its margin.
+    pub witness_b: Vec2,
+    /// Points from the target towards the swept shape, zero if they
+    /// already overlap and no contact normal could be found.
+    pub normal: Vec2,
+}
+
+/// Sweeps `shape` from `transform` along `velocity` against a static
+/// `target`, using conservative advancement on the GJK distance.
+///
+/// The swept shape has to be convex, targets without support points are
+/// handled segment by segment if they are polylines or heightfields.
+pub fn cast_shape(
+    shape: &dyn Shape,
+    transform: Affine2,
+    velocity: Vec2,
+    max_toi: f32,
+    target: &dyn Shape,
+    target_transform: Affine2,
+    target_margin: f32,
+) -> Option<ShapeCastHit> {
+    shape.support_point(transform, Vec2::X)?;
+
+    // Downcast rather than trusting `shape_type`, custom shapes may claim a
+    // built-in variant.
+    let segments = if let Some(polyline) = target.as_polyline() {
+        Some((
+            polyline.world_vertices(target_transform),
+            polyline.one_sided,
+        ))
+    } else {
+        target
+            .as_heightfield()
+            .map(|heightfield| (heightfield.world_points(target_transform), false))
+    };
+
+    let hit = match segments {
+        Some((points, one_sided)) => cast_shape_segments(
+            shape,
+            transform,
+            velocity,
+            max_toi,
+            &points,
+            one_sided,
+            target_margin,
+        ),
+        None => {
+            target.support_point(target_transform, Vec2::X)?;
+
+            cast_shape_convex(
+                shape,
+                transform,
+                velocity,
+                max_toi,
+                |direction| target.support_point(target_transform, direction),
+                target_margin,
+            )
+        }
+    }?;
+
+    if hit.normal != Vec2::ZERO {
+        return Some(hit);
+    }
+
+    // Already overlapping, the narrow phase knows which way out.
+    let moved = Affine2 {
+        translation: transform.translation + velocity * hit.time_of_impact,
+        ..transform
+    };
+    let contact = contact_shapes_with_margin(shape, moved, target, target_transform, target_margin);
+
+    Some(match contact {
+        Some(contact) => ShapeCastHit {
+            witness_a: contact.points[0],
+            witness_b: contact.points[0],
+            normal: contact.normal,
+            ..hit
+        },
+        None => hit,
+    })
+}
+
+fn cast_shape_segments(
+    shape: &dyn Shape,
+    transform: Affine2,
+    velocity: Vec2,
+    max_toi: f32,
+    points: &[Vec2],
+    one_sided: bool,
+    margin: f32,
+) -> Option<ShapeCastHit> {
+    let mut best: Option<ShapeCastHit> = None;
+
+    for segment in points.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+
+        // One-sided segments can't be hit from behind.
+        if one_sided && (b - a).perp().dot(velocity) > 0.0 {
+            continue;
+        }
+
+        let max_toi = best.map_or(max_toi, |best| best.time_of_impact);
+
+        let support = |direction: Vec2| {
+            Some(if a.dot(direction) >= b.dot(direction) {
+                a
+            } else {
+                b
+            })
+        };
+
+        let hit = cast_shape_convex(shape, transform, velocity, max_toi, support, margin);
+
+        // Extrapolated hits can land slightly past `max_toi`.
+        if let Some(hit) = hit {
+            if best.is_none_or(|best| hit.time_of_impact < best.time_of_impact) {
+                best = Some(hit);
+            }
+        }
+    }
+
+    best
+}
+
+fn cast_shape_convex(
+    shape: &dyn Shape,
+    transform: Affine2,
+    velocity: Vec2,
+    max_toi: f32,
+    target_support: impl Fn(Vec2) -> Option<Vec2>,
+    margin: f32,
+) -> Option<ShapeCastHit> {
+    let mut toi = 0.0;
+
+    for _ in 0..CAST_MAX_ITERATIONS {
+        let moved = Affine2 {
+            translation: transform.translation + velocity * toi,
+            ..transform
+        };
+
+        let Some(closest) = closest_points_gjk(
+            |direction| shape.support_point(moved, direction),
+            &target_support,
+        ) else {
+            return Some(ShapeCastHit {
+                time_of_impact: toi,
+                witness_a: moved.translation,
+                witness_b: moved.translation,
+                normal: Vec2::ZERO,
+            });
+        };
+
+        let gap = closest.distance - margin;
+        let approach_speed = -velocity.dot(closest.normal);
+
+        if gap <= CAST_TOLERANCE {
+            // GJK normals get noisy as the gap closes, so the last bit is
+            // covered by extrapolating instead.
+            let remaining = if approach_speed > 0.0 {
+                gap.max(0.0) / approach_speed
+            } else {
+                0.0
+            };
+
+            return Some(ShapeCastHit {
+                time_of_impact: toi + remaining,
+                witness_a: closest.point_a + velocity * remaining,
+                witness_b: closest.point_b + closest.normal * margin,
+                normal: closest.normal,
+            });
+        }
+
+        // Nothing on the target is closer than `gap`, so the shape can
+        // safely move until it covers that distance towards it. Stopping
+        // just short keeps the shapes apart for the next distance query.
+        if approach_speed <= 0.0 {
+            return None;
+        }
+
+        toi += (gap - 0.5 * CAST_TOLERANCE) / approach_speed;
+
+        if toi > max_toi {
+            return None;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn shape_casts() {
+        let ball = Ball::new(0.5);
+        let cuboid = Cuboid::new(vec2(1.0, 1.0));
+        let start = Affine2::from_translation(vec2(-5.0, 0.5));
+
+        let hit = cast_shape(
+            &ball,
+            start,
+            vec2(2.0, 0.0),
+            10.0,
+            &cuboid,
+            Affine2::IDENTITY,
+            0.0,
+        )
+        .unwrap();
+
+        assert_relative_eq!(hit.time_of_impact, 1.75, epsilon = 1e-3);
+        assert_relative_eq!(hit.normal, vec2(-1.0, 0.0), epsilon = 1e-3);
+        assert_relative_eq!(hit.witness_a, vec2(-1.0, 0.5), epsilon = 1e-2);
+        assert_relative_eq!(hit.witness_b.x, -1.0, epsilon = 1e-3);
+
+        // Moving away or stopping short never hits.
+        assert!(cast_shape(
+            &ball,
+            start,
+            vec2(-2.0, 0.0),
+            10.0,
+            &cuboid,
+            Affine2::IDENTITY,
+            0.0
+        )
+        .is_none());
+        assert!(cast_shape(
+            &ball,
+            start,
+            vec2(2.0, 0.0),
+            1.0,
+            &cuboid,
+            Affine2::IDENTITY,
+            0.0
+        )
+        .is_none());
+
+        // The target margin stops the shape earlier.
+        let hit = cast_shape(
+            &cuboid,
+            Affine2::from_translation(vec2(0.0, 5.0)),
+            vec2(0.0, -1.0),
+            10.0,
+            &cuboid,
+            Affine2::IDENTITY,
+            0.5,
+        )
+        .unwrap();
+
+        assert_relative_eq!(hit.time_of_impact, 2.5, epsilon = 1e-3);
+        assert_relative_eq!(hit.normal, vec2(0.0, 1.0), epsilon = 1e-3);
+
+        // Of two segments within the cast tolerance, the nearer one wins
+        // even when it comes first.
+        for x in [[2.0, 2.0005], [2.0005, 2.0]] {
+            let wall = Polyline::new(vec![
+                vec2(x[0], -1.0),
+                vec2(x[0], 1.0),
+                vec2(x[1], 1.0),
+                vec2(x[1], -1.0),
+            ]);
+            let hit = cast_shape(
+                &ball,
+                Affine2::IDENTITY,
+                vec2(1.0, 0.0),
+                10.0,
+                &wall,
+                Affine2::IDENTITY,
+                0.0,
+            )
+            .unwrap();
+
+            assert_relative_eq!(hit.time_of_impact, 1.5, epsilon = 1e-4);
+        }
+
+        let ground = Polyline::new(vec![vec2(-5.0, -1.0), vec2(0.0, 0.0), vec2(5.0, -1.0)]);
+        let hit = cast_shape(
+            &ball,
+            Affine2::from_translation(vec2(0.0, 3.0)),
+            vec2(0.0, -1.0),
+            10.0,
+            &ground,
+            Affine2::IDENTITY,
+            0.0,
+        )
+        .unwrap();
+
+        // Lands on the peak in the middle.
+        assert_relative_eq!(hit.time_of_impact, 2.5, epsilon = 1e-3);
+        assert_relative_eq!(hit.normal, vec2(0.0, 1.0), epsilon = 1e-2);
+
+        let overlapping = cast_shape(
+            &ball,
+            Affine2::IDENTITY,
+            Vec2::X,
+            10.0,
+            &cuboid,
+            Affine2::IDENTITY,
+            0.0,
+        )
+        .unwrap();
+
+        assert_eq!(overlapping.time_of_impact, 0.0);
+        assert!(overlapping.normal != Vec2::ZERO);
+    }
+}
@@ -0,0 +1,784 @@
+use std::{any::Any, f32::consts::PI};
+
+use crate::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ShapeType {
+    Ball,
+    Cuboid,
+    ConvexPolygon,
+    Capsule,
+    Polyline,
+    Heightfield,
+    /// Any shape defined outside of this crate.
+    Custom,
+}
+
+/// Gives access to the concrete type behind a `dyn Shape`.
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub trait Shape: 'static + Debug + AsAny {
+    /// Selects the specialised contact routine, custom shapes go through
+    /// the generic GJK/EPA path.
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn calculate_aabb(&self, transform: Affine2) -> AABB;
+
+    /// The furthest point of the shape along `direction`, in world space.
+    ///
+    /// Convex shapes must return `Some`, this is all the generic narrow
+    /// phase needs. Non-convex shapes return `None` and only collide through
+    /// their specialised routines.
+    fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2>;
+
+    /// Mass properties in local space for the given density.
+    ///
+    /// Shapes without an interior, like polylines, are massless by default.
+    fn mass_properties(&self, density: f32) -> MassProperties {
+        let _ = density;
+        MassProperties::default()
+    }
+
+    /// Casts `ray` against the shape, stopping after `max_toi` multiples of
+    /// `ray.dir`.
+    ///
+    /// With `solid` a ray starting inside hits at time zero, otherwise it
+    /// hits the boundary on its way out. Custom shapes can't be hit unless
+    /// they implement this.
+    fn cast_ray(
+        &self,
+        transform: Affine2,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let _ = (transform, ray, max_toi, solid);
+        None
+    }
+
+    /// The point of the shape nearest to `point`, flagged if `point` is
+    /// inside.
+    ///
+    /// With `solid` a point inside projects onto itself, otherwise onto the
+    /// boundary. Convex shapes are handled through their support points.
+    fn project_point(
+        &self,
+        transform: Affine2,
+        point: Vec2,
+        solid: bool,
+    ) -> Option<PointProjection> {
+        project_point_gjk(
+            |direction| self.support_point(transform, direction),
+            point,
+            solid,
+        )
+    }
+}
+
+/// Mass, center of mass and angular inertia of a shape or a collider.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MassProperties {
+    pub mass: f32,
+    pub local_center_of_mass: Vec2,
+    /// Moment of inertia around `local_center_of_mass`.
+    pub inertia: f32,
+}
+
+impl MassProperties {
+    pub fn new(mass: f32, local_center_of_mass: Vec2, inertia: f32) -> Self {
+        Self {
+            mass,
+            local_center_of_mass,
+            inertia,
+        }
+    }
+
+    /// Moves the properties into the space described by `transform`.
+    pub fn transformed(self, transform: Affine2) -> Self {
+        Self {
+            local_center_of_mass: transform.transform_point2(self.local_center_of_mass),
+            ..self
+        }
+    }
+
+    /// Scales the mass distribution along the body's local axes, keeping
+    /// the density.
+    ///
+    /// Inertia is exact for uniform scales and approximated by averaging the
+    /// squared axis scales otherwise.
+    pub fn scaled(self, scale: Vec2) -> Self {
+        let area_scale = (scale.x * scale.y).abs();
+
+        Self {
+            mass: self.mass * area_scale,
+            local_center_of_mass: self.local_center_of_mass * scale,
+            inertia: self.inertia * area_scale * scale.length_squared() * 0.5,
+        }
+    }
+
+    /// Keeps the shape of the mass distribution but rescales it to `mass`.
+    pub fn with_mass(self, mass: f32) -> Self {
+        let inertia = if self.mass > 0.0 {
+            self.inertia * mass / self.mass
+        } else {
+            0.0
+        };
+
+        Self {
+            mass,
+            inertia,
+            ..self
+        }
+    }
+}
+
+impl dyn Shape {
+    pub fn downcast_ref<T: Shape>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn as_ball(&self) -> Option<&Ball> {
+        self.downcast_ref()
+    }
+
+    pub fn as_cuboid(&self) -> Option<&Cuboid> {
+        self.downcast_ref()
+    }
+
+    pub fn as_convex_polygon(&self) -> Option<&ConvexPolygon> {
+        self.downcast_ref()
+    }
+
+    pub fn as_capsule(&self) -> Option<&Capsule> {
+        self.downcast_ref()
+    }
+
+    pub fn as_polyline(&self) -> Option<&Polyline> {
+        self.downcast_ref()
+    }
+
+    pub fn as_heightfield(&self) -> Option<&Heightfield> {
+        self.downcast_ref()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Ball {
+    pub radius: f32,
+}
+
+impl Ball {
+    pub fn new(radius: f32) -> Self {
+        Self { radius }
+    }
+
+    pub fn area(&self) -> f32 {
+        PI * self.radius * self.radius
+    }
+}
+
+impl Shape for Ball {
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Ball
+    }
+
+    fn calculate_aabb(&self, transform: Affine2) -> AABB {
+        let extents = rounded_extents(transform, self.radius);
+
+        AABB::new(
+            transform.translation - extents,
+            transform.translation + extents,
+        )
+    }
+
+    fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2> {
+        Some(transform.translation + rounded_support(transform, self.radius, direction))
+    }
+
+    fn mass_properties(&self, density: f32) -> MassProperties {
+        let mass = self.area() * density;
+        MassProperties::new(mass, Vec2::ZERO, 0.5 * mass * self.radius * self.radius)
+    }
+
+    fn cast_ray(
+        &self,
+        transform: Affine2,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let local_aabb = self.calculate_aabb(Affine2::IDENTITY);
+
+        cast_ray_convex(transform, local_aabb, ray, max_toi, solid, |ray| {
+            ray_entry_ball(self.radius, ray)
+        })
+    }
+
+    fn project_point(
+        &self,
+        transform: Affine2,
+        point: Vec2,
+        solid: bool,
+    ) -> Option<PointProjection> {
+        // Only a uniform scale keeps the ball round.
+        if !transform.has_uniform_scale() {
+            return project_point_gjk(
+                |direction| self.support_point(transform, direction),
+                point,
+                solid,
+            );
+        }
+
+        let center = transform.translation;
+        let radius = self.radius * transform.scale_factor();
+
+        Some(project_point_rounded_segment(center, center, radius, point, solid))
+    }
+}
+
+/// An oriented box centered at the collider origin.
+#[derive(Clone, Debug)]
+pub struct Cuboid {
+    pub half_extents: Vec2,
+}
+
+impl Cuboid {
+    pub fn new(half_extents: Vec2) -> Self {
+        Self { half_extents }
+    }
+
+    pub fn area(&self) -> f32 {
+        4.0 * self.half_extents.x * self.half_extents.y
+    }
+
+    /// Corners in local space, in counter-clockwise order.
+    pub fn local_vertices(&self) -> [Vec2; 4] {
+        let h = self.half_extents;
+
+        [
+            vec2(-h.x, -h.y),
+            vec2(h.x, -h.y),
+            vec2(h.x, h.y),
+            vec2(-h.x, h.y),
+        ]
+    }
+
+    /// Corners transformed into world space, in counter-clockwise order.
+    pub fn world_vertices(&self, transform: Affine2) -> [Vec2; 4] {
+        self.local_vertices()
+            .map(|vertex| transform.transform_point2(vertex))
+    }
+}
+
+impl Shape for Cuboid {
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Cuboid
+    }
+
+    fn calculate_aabb(&self, transform: Affine2) -> AABB {
+        let m = transform.matrix2;
+
+        // Extent of the rotated box along each world axis.
+        let extents = vec2(
+            m.x_axis.x.abs() * self.half_extents.x + m.y_axis.x.abs() * self.half_extents.y,
+            m.x_axis.y.abs() * self.half_extents.x + m.y_axis.y.abs() * self.half_extents.y,
+        );
+
+        AABB::new(
+            transform.translation - extents,
+            transform.translation + extents,
+        )
+    }
+
+    fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2> {
+        Some(furthest_point(&self.world_vertices(transform), direction))
+    }
+
+    fn mass_properties(&self, density: f32) -> MassProperties {
+        let mass = self.area() * density;
+        let size = self.half_extents * 2.0;
+
+        MassProperties::new(mass, Vec2::ZERO, mass * size.length_squared() / 12.0)
+    }
+
+    fn cast_ray(
+        &self,
+        transform: Affine2,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let local_aabb = self.calculate_aabb(Affine2::IDENTITY);
+
+        cast_ray_convex(transform, local_aabb, ray, max_toi, solid, |ray| {
+            ray_entry_aabb(&local_aabb, ray)
+        })
+    }
+}
+
+/// A convex polygon in local space, vertices are kept in counter-clockwise order.
+#[derive(Clone, Debug)]
+pub struct ConvexPolygon {
+    pub points: Vec<Vec2>,
+}
+
+impl ConvexPolygon {
+    /// Creates a polygon from convex points in either winding order.
+    pub fn new(mut points: Vec<Vec2>) -> Self {
+        assert!(points.len() >= 3, "polygon needs at least 3 points");
+
+        if signed_area(&points) < 0.0 {
+            points.reverse();
+        }
+
+        Self { points }
+    }
+
+    pub fn area(&self) -> f32 {
+        signed_area(&self.points)
+    }
+
+    /// Vertices transformed into world space, in counter-clockwise order.
+    pub fn world_vertices(&self, transform: Affine2) -> Vec<Vec2> {
+        self.points
+            .iter()
+            .map(|point| transform.transform_point2(*point))
+            .collect()
+    }
+}
+
+impl Shape for ConvexPolygon {
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::ConvexPolygon
+    }
+
+    fn calculate_aabb(&self, transform: Affine2) -> AABB {
+        let first = transform.transform_point2(self.points[0]);
+        let mut aabb = AABB::new(first, first);
+
+        for point in self.points.iter().skip(1) {
+            aabb.expand_to_include_point(transform.transform_point2(*point));
+        }
+
+        aabb
+    }
+
+    fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2> {
+        Some(furthest_point(&self.world_vertices(transform), direction))
+    }
+
+    fn mass_properties(&self, density: f32) -> MassProperties {
+        let mut area = 0.0;
+        let mut center = Vec2::ZERO;
+        let mut inertia = 0.0;
+
+        // Sum the triangles fanning out from the local origin.
+        for (i, &a) in self.points.iter().enumerate() {
+            let b = self.points[(i + 1) % self.points.len()];
+            let cross = a.perp_dot(b);
+
+            area += 0.5 * cross;
+            center += (a + b) * cross / 6.0;
+            inertia += cross / 12.0 * (a.dot(a) + a.dot(b) + b.dot(b));
+        }
+
+        if area <= 0.0 {
+            return MassProperties::default();
+        }
+
+        let center = center / area;
+        let mass = area * density;
+
+        // Shift the inertia from the origin to the centroid.
+        MassProperties::new(
+            mass,
+            center,
+            inertia * density - mass * center.length_squared(),
+        )
+    }
+
+    fn cast_ray(
+        &self,
+        transform: Affine2,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let local_aabb = self.calculate_aabb(Affine2::IDENTITY);
+
+        cast_ray_convex(transform, local_aabb, ray, max_toi, solid, |ray| {
+            ray_entry_polygon(&self.points, ray)
+        })
+    }
+}
+
+/// A segment along the local Y axis inflated by `radius`.
+#[derive(Clone, Debug)]
+pub struct Capsule {
+    pub half_height: f32,
+    pub radius: f32,
+}
+
+impl Capsule {
+    pub fn new(half_height: f32, radius: f32) -> Self {
+        Self {
+            half_height,
+            radius,
+        }
+    }
+
+    /// End points of the inner segment in local space.
+    pub fn local_segment(&self) -> [Vec2; 2] {
+        [vec2(0.0, -self.half_height), vec2(0.0, self.half_height)]
+    }
+
+    /// End points of the inner segment in world space.
+    pub fn world_segment(&self, transform: Affine2) -> [Vec2; 2] {
+        self.local_segment()
+            .map(|point| transform.transform_point2(point))
+    }
+
+    pub fn area(&self) -> f32 {
+        4.0 * self.half_height * self.radius + PI * self.radius * self.radius
+    }
+
+    /// Moment of inertia around the capsule center for a capsule of the given mass.
+    pub fn inertia(&self, mass: f32) -> f32 {
+        let (h, r) = (self.half_height, self.radius);
+
+        let density = mass / self.area();
+        let rect_mass = density * 4.0 * h * r;
+        let disc_mass = density * PI * r * r;
+
+        // Box of 2r x 2h plus the two half discs shifted by the parallel axis theorem.
+        let rect_inertia = rect_mass * (4.0 * r * r + 4.0 * h * h) / 12.0;
+        let caps_inertia = disc_mass * (0.5 * r * r + h * h + 8.0 * h * r / (3.0 * PI));
+
+        rect_inertia + caps_inertia
+    }
+}
+
+impl Shape for Capsule {
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Capsule
+    }
+
+    fn calculate_aabb(&self, transform: Affine2) -> AABB {
+        let [a, b] = self.world_segment(transform);
+        let extents = rounded_extents(transform, self.radius);
+
+        AABB::new(a.min(b) - extents, a.max(b) + extents)
+    }
+
+    fn support_point(&self, transform: Affine2, direction: Vec2) -> Option<Vec2> {
+        let end = furthest_point(&self.world_segment(transform), direction);
+        Some(end + rounded_support(transform, self.radius, direction))
+    }
+
+    fn mass_properties(&self, density: f32) -> MassProperties {
+        let mass = self.area() * density;
+        MassProperties::new(mass, Vec2::ZERO, self.inertia(mass))
+    }
+
+    fn cast_ray(
+        &self,
+        transform: Affine2,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        let local_aabb = self.calculate_aabb(Affine2::IDENTITY);
+
+        cast_ray_convex(transform, local_aabb, ray, max_toi, solid, |ray| {
+            ray_entry_capsule(self, ray)
+        })
+    }
+
+    fn project_point(
+        &self,
+        transform: Affine2,
+        point: Vec2,
+        solid: bool,
+    ) -> Option<PointProjection> {
+        if !transform.has_uniform_scale() {
+            return project_point_gjk(
+                |direction| self.support_point(transform, direction),
+                point,
+                solid,
+            );
+        }
+
+        let [a, b] = self.world_segment(transform);
+        let radius = self.radius * transform.scale_factor();
+
+        Some(project_point_rounded_segment(a, b, radius, point, solid))
+    }
+}
+
+/// An open chain of segments, meant for static terrain outlines.
+///
+/// A one-sided polyline only collides on the left side of its direction of
+/// travel, so terrain authored left to right is solid from above and can be
+/// passed through from below.
+#[derive(Clone, Debug)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub one_sided: bool,
+}
+
+impl Polyline {
+    pub fn new(points: Vec<Vec2>) -> Self {
+        assert!(points.len() >= 2, "polyline needs at least 2 points");
+
+        Self {
+            points,
+            one_sided: false,
+        }
+    }
+
+    pub fn one_sided(mut self, one_sided: bool) -> Self {
+        self.one_sided = one_sided;
+        self
+    }
+
+    pub fn world_vertices(&self, transform: Affine2) -> Vec<Vec2> {
+        self.points
+            .iter()
+            .map(|point| transform.transform_point2(*point))
+            .collect()
+    }
+}
+
+impl Shape for Polyline {
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Polyline
+    }
+
+    fn calculate_aabb(&self, transform: Affine2) -> AABB {
+        let first = transform.transform_point2(self.points[0]);
+        let mut aabb = AABB::new(first, first);
+
+        for point in self.points.iter().skip(1) {
+            aabb.expand_to_include_point(transform.transform_point2(*point));
+        }
+
+        aabb
+    }
+
+    fn support_point(&self, _transform: Affine2, _direction: Vec2) -> Option<Vec2> {
+        None
+    }
+
+    fn cast_ray(
+        &self,
+        transform: Affine2,
+        ray: &Ray,
+        max_toi: f32,
+        _solid: bool,
+    ) -> Option<RayIntersection> {
+        cast_ray_segments(&self.world_vertices(transform), self.one_sided, ray, max_toi)
+    }
+
+    fn project_point(
+        &self,
+        transform: Affine2,
+        point: Vec2,
+        _solid: bool,
+    ) -> Option<PointProjection> {
+        let nearest = project_point_segments(&self.world_vertices(transform), point)?;
+        Some(PointProjection::new(nearest, false))
+    }
+}
+
+/// Evenly spaced height samples, solid below the surface.
+///
+/// The samples span `scale.x` along the local X axis centered at the origin,
+/// and each height is multiplied by `scale.y`.
+#[derive(Clone, Debug)]
+pub struct Heightfield {
+    pub heights: Vec<f32>,
+    pub scale: Vec2,
+}
+
+impl Heightfield {
+    pub fn new(heights: Vec<f32>, scale: Vec2) -> Self {
+        assert!(heights.len() >= 2, "heightfield needs at least 2 samples");
+
+        Self { heights, scale }
+    }
+
+    pub fn cell_width(&self) -> f32 {
+        self.scale.x / (self.heights.len() - 1) as f32
+    }
+
+    /// Position of the `i`-th sample in local space.
+    pub fn local_point(&self, i: usize) -> Vec2 {
+        vec2(
+            -self.scale.x * 0.5 + i as f32 * self.cell_width(),
+            self.heights[i] * self.scale.y,
+        )
+    }
+
+    pub fn local_points(&self) -> Vec<Vec2> {
+        (0..self.heights.len())
+            .map(|i| self.local_point(i))
+            .collect()
+    }
+
+    /// Indices of the cells (segments between samples `i` and `i + 1`)
+    /// overlapping the local X range, without scanning the whole field.
+    pub fn cells_in_range(&self, min_x: f32, max_x: f32) -> std::ops::Range<usize> {
+        let half_width = self.scale.x * 0.5;
+
+        if max_x < -half_width || min_x > half_width {
+            return 0..0;
+        }
+
+        let last_cell = self.heights.len() - 2;
+        let cell = |x: f32| {
+            (((x + half_width) / self.cell_width()).floor().max(0.0) as usize).min(last_cell)
+        };
+
+        cell(min_x)..cell(max_x) + 1
+    }
+
+    /// Whether a local point lies in the solid part below the surface.
+    pub fn is_below_surface(&self, local_point: Vec2) -> bool {
+        let cells = self.cells_in_range(local_point.x, local_point.x);
+
+        match cells.clone().next().filter(|_| cells.len() == 1) {
+            Some(cell) => {
+                let (a, b) = (self.local_point(cell), self.local_point(cell + 1));
+                local_point.y <= a.y + (b.y - a.y) * (local_point.x - a.x) / (b.x - a.x)
+            }
+            None => false,
+        }
+    }
+
+    /// Samples transformed into world space, from left to right.
+    pub fn world_points(&self, transform: Affine2) -> Vec<Vec2> {
+        self.local_points()
+            .into_iter()
+            .map(|point| transform.transform_point2(point))
+            .collect()
+    }
+}
+
+impl Shape for Heightfield {
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Heightfield
+    }
+
+    fn calculate_aabb(&self, transform: Affine2) -> AABB {
+        let (min_height, max_height) = self
+            .heights
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), h| {
+                (min.min(*h), max.max(*h))
+            });
+
+        let half_width = self.scale.x * 0.5;
+        let local = AABB::from_two_points(
+            vec2(-half_width, min_height * self.scale.y),
+            vec2(half_width, max_height * self.scale.y),
+        );
+
+        let mut aabb = AABB::new(
+            transform.transform_point2(local.min),
+            transform.transform_point2(local.min),
+        );
+
+        for corner in [
+            vec2(local.max.x, local.min.y),
+            local.max,
+            vec2(local.min.x, local.max.y),
+        ] {
+            aabb.expand_to_include_point(transform.transform_point2(corner));
+        }
+
+        aabb
+    }
+
+    fn support_point(&self, _transform: Affine2, _direction: Vec2) -> Option<Vec2> {
+        None
+    }
+
+    fn cast_ray(
+        &self,
+        transform: Affine2,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        // Starting below the surface counts as a hit.
+        if solid && self.is_below_surface(transform.inverse().transform_point2(ray.origin)) {
+            return Some(RayIntersection {
+                time_of_impact: 0.0,
+                point: ray.origin,
+                normal: Vec2::ZERO,
+            });
+        }
+
+        let points = self.world_points(transform);
+
+        cast_ray_segments(&points, false, ray, max_toi)
+    }
+
+    fn project_point(
+        &self,
+        transform: Affine2,
+        point: Vec2,
+        solid: bool,
+    ) -> Option<PointProjection> {
+        let is_inside = self.is_below_surface(transform.inverse().transform_point2(point));
+
+        if solid && is_inside {
+            return Some(PointProjection::new(point, true));
+        }
+
+        let nearest = project_point_segments(&self.world_points(transform), point)?;
+        Some(PointProjection::new(nearest, is_inside))
+    }
+}
+
+/// The point with the largest projection onto `direction`.
+pub fn furthest_point(points: &[Vec2], direction: Vec2) -> Vec2 {
+    points
+        .iter()
+        .copied()
+        .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+        .unwrap()
+}
+
+/// Offset of the furthest point along `direction` of a local disc of
+/// `radius`, which becomes an ellipse under a non-uniform scale.
+fn rounded_support(transform: Affine2, radius: f32, direction: Vec2) -> Vec2 {
+    let local = (transform.matrix2.transpose() * direction).normalize_or_zero();
+    transform.matrix2 * (local * radius)
+}
+
+/// Half extents of the bounding box of a transformed local disc of `radius`.
+fn rounded_extents(transform: Affine2, radius: f32) -> Vec2 {
+    let m = transform.matrix2;
+    vec2(m.row(0).length(), m.row(1).length()) * radius
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        area += a.perp_dot(*b);
+    }
+
+    area * 0.5
+}
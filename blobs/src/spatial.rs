@@ -32,6 +32,8 @@ pub struct SpatialHash {
     pub cell_size: f32,
     pub next_id: u64,
     pub points: HashMap<u64, CellPoint>,
+    /// Upper bound of all point radii, queries widen their cell range by it.
+    pub max_radius: f32,
     #[cfg(not(feature = "use-grid"))]
     pub grid: HashMap<(i32, i32), HashSet<u64>>,
     #[cfg(feature = "use-grid")]
@@ -46,6 +48,7 @@ impl SpatialHash {
             cell_size,
             next_id: 0,
             points: HashMap::new(),
+            max_radius: 0.0,
             #[cfg(not(feature = "use-grid"))]
             grid: HashMap::new(),
             #[cfg(feature = "use-grid")]
@@ -70,6 +73,7 @@ impl SpatialHash {
 
     pub fn insert_with_id(&mut self, id: u64, position: Vec2, radius: f32) {
         let point = CellPoint { id, position, radius };
+        self.max_radius = self.max_radius.max(radius);
         let cell_coords = self.get_cell_coords(point.position);
 
         #[cfg(feature = "use-grid")]
@@ -136,20 +140,31 @@ impl SpatialHash {
         }
     }
 
-    fn get_neighbor_cells(&self, cell_coords: (i32, i32)) -> [(i32, i32); 9] {
-        let (x, y) = cell_coords;
-
-        [
-            (x - 1, y - 1),
-            (x - 1, y),
-            (x - 1, y + 1),
-            (x, y - 1),
-            (x, y),
-            (x, y + 1),
-            (x + 1, y - 1),
-            (x + 1, y),
-            (x + 1, y + 1),
-        ]
+    /// Moves an existing point or inserts a new one, updating its radius.
+    pub fn set_point(&mut self, id: u64, position: Vec2, radius: f32) {
+        if let Some(point) = self.points.get_mut(&id) {
+            point.radius = radius;
+            self.max_radius = self.max_radius.max(radius);
+
+            let offset = position - point.position;
+            self.move_point(id, offset);
+        } else {
+            self.insert_with_id(id, position, radius);
+        }
+    }
+
+    /// Cells that can hold a point within `query_radius` of `position`,
+    /// which spans more than the direct neighbours for large radii.
+    fn get_query_cells(
+        &self,
+        position: Vec2,
+        query_radius: f32,
+    ) -> impl Iterator<Item = CellIndex> {
+        let reach = Vec2::splat(query_radius + self.max_radius);
+        let (min_x, min_y) = self.get_cell_coords(position - reach);
+        let (max_x, max_y) = self.get_cell_coords(position + reach);
+
+        (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
     }
 
     pub fn query(
@@ -157,12 +172,9 @@ impl SpatialHash {
         position: Vec2,
         query_radius: f32,
     ) -> &Vec<CellPoint> {
-        let cell_coords = self.get_cell_coords(position);
-        let neighbor_cells = self.get_neighbor_cells(cell_coords);
-
         self.query_results.clear();
 
-        for cell_coords in neighbor_cells {
+        for cell_coords in self.get_query_cells(position, query_radius) {
             #[cfg(feature = "use-grid")]
             {
                 for point_id in self.grid[cell_coords].iter() {
@@ -88,4 +88,648 @@ mod tests {
         let results = hash.query(Vec2::new(0.0, 0.0), 1.5);
         assert!(results.is_empty());
     }
+
+    #[test]
+    fn ball_rests_on_static_cuboid() {
+        let mut physics = Physics::new(vec2(0.0, -10.0));
+
+        let ground = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .body_type(RigidBodyType::Static)
+                .build(),
+        );
+        physics.insert_collider_with_parent(
+            ColliderBuilder::new()
+                .shape(Box::new(Cuboid::new(vec2(5.0, 0.5))))
+                .build(),
+            ground,
+        );
+
+        let ball = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(0.0, 2.0)).build());
+        physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), ball);
+
+        for _ in 0..120 {
+            physics.step(1.0 / 60.0, &mut NoopEventHandler);
+        }
+
+        assert_approx_eq!(physics.rbd_position(ground).unwrap().y, 0.0);
+        assert_approx_eq!(physics.rbd_position(ball).unwrap().y, 1.0, 0.05);
+    }
+
+    #[test]
+    fn margin_keeps_shapes_apart() {
+        let mut physics = Physics::new(vec2(0.0, -10.0));
+
+        let ground = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .body_type(RigidBodyType::Static)
+                .build(),
+        );
+        physics.insert_collider_with_parent(ColliderBuilder::cuboid(5.0, 0.5).build(), ground);
+
+        let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(0.0, 2.0)).build());
+        physics.insert_collider_with_parent(
+            ColliderBuilder::cuboid(0.5, 0.5).margin(0.2).build(),
+            body,
+        );
+
+        for _ in 0..120 {
+            physics.step(1.0 / 60.0, &mut NoopEventHandler);
+        }
+
+        assert_approx_eq!(physics.rbd_position(body).unwrap().y, 1.2, 0.05);
+    }
+
+    #[test]
+    fn compound_body_mass_properties() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let body = physics.insert_rbd(RigidBodyBuilder::new().build());
+
+        for x in [-1.0, 3.0] {
+            physics.insert_collider_with_parent(
+                ColliderBuilder::ball(0.5)
+                    .offset(Affine2::from_translation(vec2(x, 0.0)))
+                    .build(),
+                body,
+            );
+        }
+
+        let rbd = physics.rbd_set.get(body).unwrap();
+        let ball_mass = std::f32::consts::PI * 0.25;
+
+        assert_approx_eq!(rbd.calculated_mass, 2.0 * ball_mass);
+        assert_approx_eq!(rbd.center_of_mass.x, 1.0);
+        assert_approx_eq!(
+            rbd.inertia,
+            2.0 * (0.5 * ball_mass * 0.25 + ball_mass * 4.0),
+            1e-4
+        );
+    }
+
+    #[test]
+    fn scaled_body_updates_colliders() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let body = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .position(vec2(1.0, 0.0))
+                .rotation(std::f32::consts::FRAC_PI_2)
+                .build(),
+        );
+        let collider = physics.insert_collider_with_parent(
+            ColliderBuilder::cuboid(1.0, 0.5)
+                .offset(Affine2::from_translation(vec2(1.0, 0.0)))
+                .build(),
+            body,
+        );
+
+        let mass = physics.get_rbd(body).unwrap().calculated_mass;
+
+        physics.set_rbd_scale(body, vec2(2.0, 3.0));
+
+        let rbd = physics.get_rbd(body).unwrap();
+        let col = physics.get_col(collider).unwrap();
+
+        assert_approx_eq!(rbd.calculated_mass, mass * 6.0, 1e-4);
+        assert_approx_eq!(rbd.center_of_mass.x, 2.0);
+        assert_approx_eq!(col.absolute_rotation(), std::f32::consts::FRAC_PI_2);
+
+        // The scaled box is 4 wide and 3 tall before the body rotation.
+        let aabb = col.calculate_aabb();
+        assert_approx_eq!(aabb.size().x, 3.0, 1e-4);
+        assert_approx_eq!(aabb.size().y, 4.0, 1e-4);
+        assert_approx_eq!(aabb.center().y, 2.0, 1e-4);
+    }
+
+    #[test]
+    fn scaled_body_keeps_absolute_mass_overrides() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(3.0, 0.0)).build());
+        let collider = physics.insert_collider_with_parent(
+            ColliderBuilder::ball(0.5).mass_override(1.0).build(),
+            body,
+        );
+
+        physics.set_rbd_scale(body, vec2(2.0, 2.0));
+
+        assert_approx_eq!(physics.get_rbd(body).unwrap().calculated_mass, 1.0, 1e-4);
+        // Measured to the corners of the scaled ball's bounding box.
+        let radius = physics.get_col(collider).unwrap().bounding_radius();
+        assert_approx_eq!(radius, 2f32.sqrt(), 1e-4);
+    }
+
+    #[test]
+    fn stretched_ball_collides_as_ellipse() {
+        let ball = Ball::new(1.0);
+        let ground = Cuboid::new(vec2(5.0, 0.5));
+
+        // Stretched twice as tall, the ball reaches 2 units below its center.
+        let stretched = Affine2::from_scale_angle_translation(vec2(1.0, 2.0), 0.0, vec2(0.0, 2.4));
+
+        let aabb = ball.calculate_aabb(stretched);
+        assert_approx_eq!(aabb.min.y, 0.4, 1e-5);
+
+        let contact = contact_shapes(&ball, stretched, &ground, Affine2::IDENTITY).unwrap();
+        assert_approx_eq!(contact.depth, 0.1, 1e-3);
+        assert_approx_eq!(contact.normal.y, 1.0, 1e-3);
+    }
+
+    fn collision_pairs(broad_phase: impl BroadPhase + 'static) -> Vec<ColliderPair> {
+        let mut physics = Physics::new(vec2(0.0, -10.0)).with_broad_phase(broad_phase);
+        physics.substeps = 1;
+
+        let ground = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .body_type(RigidBodyType::Static)
+                .build(),
+        );
+        physics.insert_collider_with_parent(ColliderBuilder::cuboid(20.0, 0.5).build(), ground);
+
+        // Deterministic scatter of overlapping balls and boxes.
+        let mut seed = 12345u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1 << 24) as f32
+        };
+
+        for i in 0..200 {
+            let position = vec2(next() * 30.0 - 15.0, next() * 10.0);
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(position).build());
+
+            let collider = if i % 5 == 0 {
+                ColliderBuilder::cuboid(next() + 0.2, next() * 0.5 + 0.2)
+            } else {
+                ColliderBuilder::ball(next() * 0.6 + 0.1)
+            };
+
+            physics.insert_collider_with_parent(
+                collider.active_events(ActiveEvents::COLLISION_EVENTS).build(),
+                body,
+            );
+        }
+
+        let mut handler = ChannelEventHandler::new();
+
+        physics.step(1.0 / 60.0, &mut handler);
+
+        let mut pairs = handler
+            .collision_recv
+            .try_iter()
+            .map(|event| (event.col_handle_a, event.col_handle_b))
+            .collect::<Vec<_>>();
+
+        pairs.sort_by_key(|(a, b)| (a.0, b.0));
+        pairs
+    }
+
+    /// A world with a static body at the origin carrying `ground`.
+    fn world_with_ground(
+        gravity: Vec2,
+        ground: Collider,
+    ) -> (Physics, RigidBodyHandle, ColliderHandle) {
+        let mut physics = Physics::new(gravity);
+
+        let body = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .body_type(RigidBodyType::Static)
+                .build(),
+        );
+        let collider = physics.insert_collider_with_parent(ground, body);
+
+        (physics, body, collider)
+    }
+
+    /// Adds a dynamic body at `position` carrying `collider`.
+    fn insert_body(
+        physics: &mut Physics,
+        position: Vec2,
+        collider: Collider,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        let body = physics.insert_rbd(RigidBodyBuilder::new().position(position).build());
+        let collider = physics.insert_collider_with_parent(collider, body);
+
+        (body, collider)
+    }
+
+    fn step_for(physics: &mut Physics, steps: usize, events: &mut dyn EventHandler) {
+        for _ in 0..steps {
+            physics.step(1.0 / 60.0, events);
+        }
+    }
+
+    #[test]
+    fn spatial_hash_matches_brute_force() {
+        let brute_force = collision_pairs(BruteForceBroadPhase);
+
+        assert!(brute_force.len() > 20);
+        assert_eq!(collision_pairs(SpatialHashBroadPhase::default()), brute_force);
+    }
+
+    #[test]
+    fn spatial_hash_skips_distant_oversized_pairs() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let ground = physics.insert_rbd(
+            RigidBodyBuilder::new()
+                .body_type(RigidBodyType::Static)
+                .build(),
+        );
+        let ground_col =
+            physics.insert_collider_with_parent(ColliderBuilder::cuboid(20.0, 0.5).build(), ground);
+
+        let balls = [vec2(0.0, 0.8), vec2(0.0, 30.0)].map(|position| {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(position).build());
+            physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body)
+        });
+
+        let pairs = SpatialHashBroadPhase::default().find_pairs(&physics.col_set);
+
+        assert_eq!(pairs, vec![ordered_pair(ground_col, balls[0])]);
+    }
+
+    #[test]
+    fn bvh_matches_brute_force() {
+        assert_eq!(
+            collision_pairs(BvhBroadPhase::default()),
+            collision_pairs(BruteForceBroadPhase)
+        );
+    }
+
+    #[test]
+    fn sweep_and_prune_matches_brute_force() {
+        assert_eq!(
+            collision_pairs(SweepAndPrune::new()),
+            collision_pairs(BruteForceBroadPhase)
+        );
+    }
+
+    #[test]
+    fn custom_broad_phase() {
+        /// Only lets colliders on the same side of the y axis collide.
+        struct SplitBroadPhase;
+
+        impl BroadPhase for SplitBroadPhase {
+            fn find_pairs(&mut self, col_set: &ColliderSet) -> Vec<ColliderPair> {
+                BruteForceBroadPhase
+                    .find_pairs(col_set)
+                    .into_iter()
+                    .filter(|(a, b)| {
+                        let side = |handle| col_set.get(handle).unwrap().absolute_translation().x > 0.0;
+                        side(*a) == side(*b)
+                    })
+                    .collect()
+            }
+        }
+
+        let mut physics = Physics::new(Vec2::ZERO).with_broad_phase(SplitBroadPhase);
+
+        for x in [-0.3, 0.3] {
+            let body = physics.insert_rbd(RigidBodyBuilder::new().position(vec2(x, 0.0)).build());
+            physics.insert_collider_with_parent(
+                ColliderBuilder::ball(0.5)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .build(),
+                body,
+            );
+        }
+
+        let mut handler = ChannelEventHandler::new();
+
+        physics.step(1.0 / 60.0, &mut handler);
+        assert_eq!(handler.collision_recv.try_iter().count(), 0);
+
+        physics.set_broad_phase(Box::new(BruteForceBroadPhase));
+        physics.step(1.0 / 60.0, &mut handler);
+        assert!(handler.collision_recv.try_iter().count() > 0);
+    }
+
+    #[test]
+    fn contact_started_and_stopped_once() {
+        let (mut physics, _, ground_col) =
+            world_with_ground(vec2(0.0, -10.0), ColliderBuilder::cuboid(5.0, 0.5).build());
+
+        let (ball, ball_col) = insert_body(
+            &mut physics,
+            vec2(0.0, 1.2),
+            ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .build(),
+        );
+
+        let mut handler = ChannelEventHandler::new();
+        step_for(&mut physics, 60, &mut handler);
+
+        let events = handler.collision_recv.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, CollisionEventKind::Started);
+        assert_eq!(
+            physics.contact_pair(ground_col, ball_col).unwrap().kind,
+            CollisionEventKind::Ongoing
+        );
+
+        // Teleport the ball away from the ground.
+        let body = physics.get_mut_rbd(ball).unwrap();
+        body.position = vec2(0.0, 10.0);
+        body.position_old = body.position;
+
+        step_for(&mut physics, 3, &mut handler);
+
+        let events = handler.collision_recv.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, CollisionEventKind::Stopped);
+        assert!(physics.contact_pair(ball_col, ground_col).is_none());
+    }
+
+    #[test]
+    fn removal_stops_pairs_immediately() {
+        let (mut physics, _, sensor) = world_with_ground(
+            Vec2::ZERO,
+            ColliderBuilder::cuboid(2.0, 0.5)
+                .flags(ColliderFlags { is_sensor: true })
+                .active_events(ActiveEvents::SENSOR_EVENTS)
+                .build(),
+        );
+
+        let bodies = [-0.3, 0.3].map(|x| {
+            let ball = ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .build();
+            insert_body(&mut physics, vec2(x, 0.0), ball)
+        });
+        let colliders = bodies.map(|(_, collider)| collider);
+
+        let mut handler = ChannelEventHandler::new();
+        physics.step(1.0 / 60.0, &mut handler);
+
+        assert!(physics.contact_pair(colliders[0], colliders[1]).is_some());
+        assert_eq!(physics.sensor_overlaps(sensor).len(), 2);
+        handler.collision_recv.try_iter().for_each(drop);
+        handler.sensor_recv.try_iter().for_each(drop);
+
+        physics.remove_rbd(bodies[1].0);
+
+        assert!(physics.contact_pair(colliders[0], colliders[1]).is_none());
+        assert_eq!(physics.sensor_overlaps(sensor), vec![colliders[0]]);
+
+        // Delivered at the start of the next step, before anything moves.
+        physics.step(1.0 / 60.0, &mut handler);
+
+        let events = handler.collision_recv.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, CollisionEventKind::Stopped);
+
+        assert_eq!(
+            handler.sensor_recv.try_iter().collect::<Vec<_>>(),
+            vec![SensorEvent {
+                kind: SensorEventKind::Exit,
+                sensor,
+                other: colliders[1],
+            }]
+        );
+    }
+
+    #[test]
+    fn collision_event_manifold() {
+        let (mut physics, _, _) =
+            world_with_ground(vec2(0.0, -10.0), ColliderBuilder::cuboid(5.0, 0.5).build());
+
+        let (_, ball_col) = insert_body(
+            &mut physics,
+            vec2(1.0, 3.0),
+            ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .build(),
+        );
+
+        let mut handler = ChannelEventHandler::new();
+        step_for(&mut physics, 60, &mut handler);
+
+        let event = handler.collision_recv.try_recv().unwrap();
+        assert_eq!(event.col_handle_a, ball_col);
+
+        let manifold = event.manifold;
+        assert_approx_eq!(manifold.normal.y, 1.0, 1e-4);
+        assert!(manifold.depth > 0.0);
+        assert!(manifold.relative_normal_velocity < -1.0);
+        assert_eq!(manifold.points().len(), 1);
+        assert_approx_eq!(manifold.points()[0].x, 1.0, 1e-3);
+        assert_approx_eq!(manifold.points()[0].y, 0.5, 0.1);
+        assert_approx_eq!(manifold.correction_a.y, manifold.depth, 1e-5);
+        assert_eq!(manifold.correction_b, Vec2::ZERO);
+    }
+
+    #[test]
+    fn sensor_enter_and_exit() {
+        let (mut physics, _, sensor) = world_with_ground(
+            vec2(0.0, -10.0),
+            ColliderBuilder::cuboid(2.0, 0.5)
+                .flags(ColliderFlags { is_sensor: true })
+                .active_events(ActiveEvents::SENSOR_EVENTS)
+                .build(),
+        );
+
+        let (ball, ball_col) =
+            insert_body(&mut physics, vec2(0.0, 1.0), ColliderBuilder::ball(0.2).build());
+
+        let mut handler = ChannelEventHandler::new();
+        let mut overlapped = false;
+
+        for _ in 0..60 {
+            physics.step(1.0 / 60.0, &mut handler);
+            overlapped |= physics.sensor_overlaps(sensor) == vec![ball_col];
+        }
+
+        assert!(overlapped);
+        assert!(physics.sensor_overlaps(sensor).is_empty());
+        assert!(physics.rbd_position(ball).unwrap().y < -1.0);
+        assert_eq!(handler.collision_recv.try_iter().count(), 0);
+
+        let events = handler.sensor_recv.try_iter().collect::<Vec<_>>();
+        let expected = |kind| SensorEvent {
+            kind,
+            sensor,
+            other: ball_col,
+        };
+
+        assert_eq!(
+            events,
+            vec![
+                expected(SensorEventKind::Enter),
+                expected(SensorEventKind::Exit)
+            ]
+        );
+    }
+
+    #[test]
+    fn events_require_opt_in() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        // Two overlapping pairs, in the second one only the left ball opts in.
+        let mut colliders = vec![];
+
+        for (x, y) in [(-0.3, 0.0), (0.3, 0.0), (-0.3, 5.0), (0.3, 5.0)] {
+            let active_events = if colliders.len() == 2 {
+                ActiveEvents::COLLISION_EVENTS
+            } else {
+                ActiveEvents::empty()
+            };
+
+            let ball = ColliderBuilder::ball(0.5).active_events(active_events).build();
+            colliders.push(insert_body(&mut physics, vec2(x, y), ball).1);
+        }
+
+        let mut handler = ChannelEventHandler::new();
+        physics.step(1.0 / 60.0, &mut handler);
+
+        let events = handler.collision_recv.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            ordered_pair(events[0].col_handle_a, events[0].col_handle_b),
+            ordered_pair(colliders[2], colliders[3])
+        );
+        // Pairs without events are still tracked.
+        assert!(physics.contact_pair(colliders[0], colliders[1]).is_some());
+    }
+
+    #[test]
+    fn sensor_overlaps_without_events() {
+        let (mut physics, _, sensor) = world_with_ground(
+            Vec2::ZERO,
+            ColliderBuilder::cuboid(2.0, 0.5)
+                .flags(ColliderFlags { is_sensor: true })
+                .build(),
+        );
+
+        let (_, ball_col) =
+            insert_body(&mut physics, Vec2::ZERO, ColliderBuilder::ball(0.2).build());
+
+        let mut handler = ChannelEventHandler::new();
+        physics.step(1.0 / 60.0, &mut handler);
+
+        assert_eq!(physics.sensor_overlaps(sensor), vec![ball_col]);
+        assert_eq!(handler.sensor_recv.try_iter().count(), 0);
+    }
+
+    #[test]
+    fn removal_events_delivered_on_next_step() {
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let (body, first) =
+            insert_body(&mut physics, Vec2::ZERO, ColliderBuilder::ball(0.5).build());
+        let second = physics.insert_collider_with_parent(ColliderBuilder::ball(0.5).build(), body);
+
+        let mut handler = RingBufferEventHandler::new(8);
+
+        physics.remove_col(first);
+        assert!(handler.removals.is_empty());
+
+        physics.step(1.0 / 60.0, &mut handler);
+        assert_eq!(handler.removals, [RemovalEvent::Collider(first)]);
+
+        // Removing the last collider takes the body with it.
+        handler.removals.clear();
+        physics.remove_col(second);
+        physics.step(1.0 / 60.0, &mut handler);
+
+        assert_eq!(
+            handler.removals,
+            [
+                RemovalEvent::Collider(second),
+                RemovalEvent::RigidBody(body)
+            ]
+        );
+    }
+
+    #[test]
+    fn hooks_filter_pairs() {
+        /// Bullets ignore their shooter for a while after being fired.
+        struct IgnoreShooter {
+            bullet: ColliderHandle,
+            shooter: ColliderHandle,
+            until: f64,
+        }
+
+        impl PhysicsHooks for IgnoreShooter {
+            fn filter_contact_pair(&mut self, pair: &PairContext) -> bool {
+                let is_bullet_pair = ordered_pair(pair.collider_a, pair.collider_b)
+                    == ordered_pair(self.bullet, self.shooter);
+
+                !is_bullet_pair || pair.time >= self.until
+            }
+        }
+
+        let mut physics = Physics::new(Vec2::ZERO);
+
+        let colliders = [-0.3, 0.3]
+            .map(|x| insert_body(&mut physics, vec2(x, 0.0), ColliderBuilder::ball(0.5).build()).1);
+
+        physics.set_hooks(Box::new(IgnoreShooter {
+            bullet: colliders[1],
+            shooter: colliders[0],
+            until: 0.05,
+        }));
+
+        physics.step(1.0 / 60.0, &mut NoopEventHandler);
+        assert_approx_eq!(physics.col_position(colliders[1]).unwrap().x, 0.3);
+
+        step_for(&mut physics, 5, &mut NoopEventHandler);
+
+        assert!(physics.col_position(colliders[1]).unwrap().x > 0.45);
+    }
+
+    #[test]
+    fn hooks_modify_contacts() {
+        /// Lets bodies through the platform while they move upwards.
+        struct OneWayPlatform(RigidBodyHandle);
+
+        impl PhysicsHooks for OneWayPlatform {
+            fn modify_contact(&mut self, pair: &PairContext, contact: &mut ContactModification) {
+                let other = if pair.rigid_body_a == self.0 {
+                    pair.rigid_body_b
+                } else {
+                    pair.rigid_body_a
+                };
+
+                contact.pass_through = pair.rbd_set.get(other).unwrap().calculated_velocity.y > 0.0;
+            }
+        }
+
+        let (mut physics, platform, _) =
+            world_with_ground(vec2(0.0, -10.0), ColliderBuilder::cuboid(5.0, 0.25).build());
+        physics.set_hooks(Box::new(OneWayPlatform(platform)));
+
+        // Starts below the platform moving up, so it jumps through and lands on top.
+        let (ball, _) =
+            insert_body(&mut physics, vec2(0.0, -1.0), ColliderBuilder::ball(0.25).build());
+
+        physics.step(1.0 / 60.0, &mut NoopEventHandler);
+        physics.get_mut_rbd(ball).unwrap().set_velocity(vec2(0.0, 6.0));
+
+        step_for(&mut physics, 180, &mut NoopEventHandler);
+
+        assert_approx_eq!(physics.rbd_position(ball).unwrap().y, 0.5, 0.05);
+    }
+
+    #[test]
+    fn event_history_is_per_world() {
+        let mut physics = Physics::new(Vec2::ZERO);
+        let other = Physics::new(Vec2::ZERO);
+
+        let (body, collider) =
+            insert_body(&mut physics, Vec2::ZERO, ColliderBuilder::ball(0.5).build());
+
+        physics.step(0.5, &mut NoopEventHandler);
+        physics.remove_col(collider);
+
+        let history = physics.event_history();
+        let events = history.for_rigid_body(body).collect::<Vec<_>>();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].col_handle, Some(collider));
+        assert_eq!(events[0].time_data.unpaused_time, 0.5);
+        assert_eq!(history.in_time_range(..0.5).count(), 0);
+        assert!(other.event_history().is_empty());
+    }
 }
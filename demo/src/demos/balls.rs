@@ -93,65 +93,35 @@ impl Demo for BallsDemo {
         if is_key_down(KeyCode::Key1) {
             self.enable_autospawn = !self.enable_autospawn;
         }
-        let mut physics = self.physics_mut();
+        let mut physics = self.sim.physics.borrow_mut();
         let physics = &mut *physics;
 
-        physics.fixed_step(c.delta);
+        physics.fixed_step(c.delta, &mut NoopEventHandler);
 
         let mut wants_ball = false;
         let position = random_around(vec2(1.0, 1.0), 0.1, 0.2);
 
-        while let Ok(_) = physics.collision_recv.try_recv() {
-            // Drain collision events
-        }
+        // Only colliders under the cursor are tested, whatever their shape.
+        let mut hover = None;
+
+        physics.query_pipeline.intersections_with_point(
+            &physics.rbd_set,
+            &physics.col_set,
+            &c.mouse_world,
+            QueryFilter::new().exclude_rigid_body(self.mouse_rbd),
+            |handle| {
+                hover = physics.col_set.get(handle).and_then(|collider| {
+                    Some(HoverState {
+                        index: collider.parent?,
+                        position: collider.absolute_translation(),
+                    })
+                });
+
+                hover.is_none()
+            },
+        );
 
-        // for (index, object) in sim.balls.iter() {
-        //     let collider = sim.physics.col_set.arena.get(index).unwrap();
-        //     let rbd_handle = collider.parent.unwrap();
-        //
-        //     let mut hovered = false;
-        //
-        //     if mouse_rbd != rbd_handle {
-        //         if mouse_world.distance(collider.absolute_translation()) < collider.radius {
-        //             hovered = true;
-        //
-        //             hover = Some(HoverState {
-        //                 index: rbd_handle,
-        //                 position: collider.absolute_translation(),
-        //             });
-        //         }
-        //     }
-        //
-        //     let color = if hovered {
-        //         RED.mix(object.color, 0.2)
-        //     } else {
-        //         object.color
-        //     };
-        //
-        //     let rbd = sim.physics.get_rbd(rbd_handle).unwrap();
-        //
-        //     draw_circle(collider.absolute_translation(), collider.radius, color);
-        //     let a = collider.absolute_translation();
-        //     let b = a + vec2(rbd.rotation.cos(), rbd.rotation.sin()) * 0.4;
-        //     draw_line(a.x, a.y, b.x, b.y, 0.05, YELLOW);
-        //
-        //     let r = collider.radius;
-        //
-        //     draw_texture_ex(
-        //         texture,
-        //         collider.absolute_translation().x - r,
-        //         collider.absolute_translation().y - r,
-        //         color.alpha(0.4),
-        //         DrawTextureParams {
-        //             dest_size: Some(macroquad::prelude::vec2(
-        //                 collider.radius * 2.0,
-        //                 collider.radius * 2.0,
-        //             )),
-        //             rotation: rbd.rotation,
-        //             ..Default::default()
-        //         },
-        //     );
-        // }
+        self.hover = hover;
 
         if physics.rbd_set.len() < 200 && self.enable_autospawn {
             if c.cooldowns.can_use("ball", 0.1) {
@@ -46,7 +46,7 @@ fn window_conf() -> Conf {
 }
 
 fn make_world(gravity: Vec2) -> Simulation {
-    let mut blob_physics = blobs::Physics::new(gravity, false);
+    let mut blob_physics = blobs::Physics::new(gravity);
 
     blob_physics.constraints.push(Constraint {
         position: Vec2::ZERO,
@@ -347,7 +347,57 @@ async fn main() {
 
     pub fn debug_draw_physics(debug: DebugData, mouse_world: Vec2, draw_bodies: bool) {
         for collider in debug.colliders.iter() {
-            draw_circle(collider.transform.translation, collider.radius, BLUE);
+            match &collider.shape {
+                DebugShape::Ball { radius } => {
+                    let radius = *radius * collider.transform.scale_factor();
+                    draw_circle(collider.transform.translation, radius, BLUE);
+                }
+                DebugShape::Cuboid { half_extents } => {
+                    let corners = Cuboid::new(*half_extents).world_vertices(collider.transform);
+                    draw_polygon_outline(&corners, 0.05, BLUE);
+                }
+                DebugShape::ConvexPolygon { points } => {
+                    let points = points
+                        .iter()
+                        .map(|p| collider.transform.transform_point2(*p))
+                        .collect::<Vec<_>>();
+                    draw_polygon_outline(&points, 0.05, BLUE);
+                }
+                DebugShape::Capsule {
+                    half_height,
+                    radius,
+                } => {
+                    let [a, b] = Capsule::new(*half_height, *radius)
+                        .world_segment(collider.transform);
+                    let radius = *radius * collider.transform.scale_factor();
+                    let side = (b - a).perp().normalize_or_zero() * radius;
+
+                    draw_circle(a, radius, BLUE);
+                    draw_circle(b, radius, BLUE);
+                    draw_line(a.x + side.x, a.y + side.y, b.x + side.x, b.y + side.y, 0.05, BLUE);
+                    draw_line(a.x - side.x, a.y - side.y, b.x - side.x, b.y - side.y, 0.05, BLUE);
+                }
+                DebugShape::Heightfield { heights, scale } => {
+                    let points = Heightfield::new(heights.clone(), *scale).local_points();
+
+                    for segment in points.windows(2) {
+                        let a = collider.transform.transform_point2(segment[0]);
+                        let b = collider.transform.transform_point2(segment[1]);
+
+                        draw_line(a.x, a.y, b.x, b.y, 0.05, GREEN);
+                    }
+                }
+                DebugShape::Polyline { points, one_sided } => {
+                    let color = if *one_sided { SKYBLUE } else { BLUE };
+
+                    for segment in points.windows(2) {
+                        let a = collider.transform.transform_point2(segment[0]);
+                        let b = collider.transform.transform_point2(segment[1]);
+
+                        draw_line(a.x, a.y, b.x, b.y, 0.05, color);
+                    }
+                }
+            }
 
             let r = collider.radius;
 
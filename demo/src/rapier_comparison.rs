@@ -1,6 +1,6 @@
 use blobs::{
     perf_counters::{self, perf_counters_new_frame},
-    Constraint, ZipTuple,
+    Constraint, NoopEventHandler, ZipTuple,
 };
 use std::any::Any;
 use thunderdome::{Arena, Index};
@@ -48,7 +48,7 @@ fn window_conf() -> Conf {
 }
 
 fn make_world(gravity: Vec2) -> Simulation {
-    let mut blob_physics = blobs::Physics::new(gravity, false);
+    let mut blob_physics = blobs::Physics::new(gravity);
 
     blob_physics.constraints.push(Constraint {
         position: Vec2::ZERO,
@@ -191,7 +191,7 @@ async fn main() {
             let start = instant::now();
 
             if frame_index > 20 {
-                sim.physics.step(delta as f64);
+                sim.physics.step(delta as f64, &mut NoopEventHandler);
             }
 
             let end = instant::now();
@@ -107,11 +107,13 @@ pub fn collider_from_desc(
         absolute_transform: Affine2::from_translation(desc.position),
         user_data: id.to_bits() as u128,
         parent: Some(parent),
-        radius: desc.radius,
+        margin: 0.0,
+        density: 1.0,
         mass_override: None,
         flags: ColliderFlags {
             is_sensor: desc.is_sensor,
         },
+        active_events: ActiveEvents::empty(),
         collision_groups: desc.collision_groups,
         shape: Box::new(Ball {
             radius: desc.radius,
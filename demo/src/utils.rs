@@ -115,6 +115,12 @@ pub fn draw_circle(position: Vec2, radius: f32, color: Color) {
     );
 }
 
+pub fn draw_polygon_outline(points: &[Vec2], thickness: f32, color: Color) {
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        draw_line(a.x, a.y, b.x, b.y, thickness, color);
+    }
+}
+
 pub trait ColorExtensions {
     fn alpha(&self, value: f32) -> Color;
     fn mix(&self, other: Color, value: f32) -> Color;